@@ -0,0 +1,189 @@
+"""Expansion of zip and tar archives into virtual entries for their members."""
+
+from __future__ import annotations
+
+import logging
+import posixpath
+import tarfile
+import zipfile
+from pathlib import Path
+from typing import Any, Dict, Iterator, List, Optional, Tuple
+
+from ...utils.file_utils.mime_detection import DEFAULT_RULES, ClassificationRules
+from .file_processor import process_bytes
+
+logger = logging.getLogger(__name__)
+
+ARCHIVE_SUFFIXES = (
+    ".zip",
+    ".tar",
+    ".tar.gz",
+    ".tgz",
+    ".tar.bz2",
+    ".tbz2",
+    ".tar.xz",
+    ".txz",
+)
+
+# ``(path inside the archive, info)`` for one archive member.
+ArchiveMember = Tuple[str, Dict[str, Any]]
+# ``(path inside the archive, declared size, reader)``; the reader returns at
+# most the requested number of bytes of the member's decompressed data.
+_RawMember = Tuple[str, int, Any]
+
+
+def is_archive(file_path: Path) -> bool:
+    """Return whether ``file_path`` has the suffix of a supported archive format."""
+
+    return file_path.name.lower().endswith(ARCHIVE_SUFFIXES)
+
+
+def _member_path(name: str) -> Optional[str]:
+    """Normalise a member name, rejecting names that point outside the archive."""
+
+    normalized = posixpath.normpath(name.replace("\\", "/")).lstrip("/")
+    if normalized in {"", "."} or normalized == ".." or normalized.startswith("../"):
+        return None
+    return normalized
+
+
+def _zip_members(file_path: Path) -> Iterator[_RawMember]:
+    with zipfile.ZipFile(file_path) as archive:
+        for info in archive.infolist():
+            if info.is_dir():
+                continue
+
+            def read(limit: int, info: zipfile.ZipInfo = info) -> bytes:
+                with archive.open(info) as member:
+                    return member.read(limit)
+
+            yield info.filename, info.file_size, read
+
+
+def _tar_members(file_path: Path) -> Iterator[_RawMember]:
+    with tarfile.open(file_path) as archive:
+        for info in archive:
+            if not info.isfile():
+                continue
+
+            def read(limit: int, info: tarfile.TarInfo = info) -> bytes:
+                member = archive.extractfile(info)
+                if member is None:
+                    return b""
+                with member:
+                    return member.read(limit)
+
+            yield info.name, info.size, read
+
+
+def process_archive(
+    file_path: Path,
+    max_file_size: int,
+    include_binary: bool,
+    encoding: Optional[str] = None,
+    hashing_enabled: bool = True,
+    hash_encoding: Optional[str] = None,
+    binary_preview_bytes: Optional[int] = None,
+    classification_rules: ClassificationRules = DEFAULT_RULES,
+) -> Optional[List[ArchiveMember]]:
+    """
+    Processes every file inside a zip or tar archive like a regular file.
+
+    Members are decompressed in memory and handed to :func:`process_bytes`,
+    so they are classified and previewed exactly like files on disk. No more
+    than ``max_file_size`` bytes of a member are decompressed; larger members
+    are reported as excluded, whatever size the archive declares for them.
+    Archives nested inside the archive are not expanded.
+
+    Args:
+        file_path (Path): The archive to expand.
+        max_file_size (int): Members larger than this are excluded.
+        include_binary (bool): Whether binary members are included.
+        encoding (Optional[str]): Encoding hint for text members.
+        hashing_enabled (bool): Whether to report ``file_hash`` for members.
+        hash_encoding (Optional[str]): Encoding of ``file_hash``.
+        binary_preview_bytes (Optional[int]): Cap for binary member previews.
+        classification_rules (ClassificationRules): Rules adjusting the
+            text/binary classification of members.
+
+    Returns:
+        Optional[List[ArchiveMember]]: The members as ``(path inside the
+        archive, info)`` pairs, or None if the archive cannot be read.
+    """
+    name = file_path.name.lower()
+    iterate = _zip_members if name.endswith(".zip") else _tar_members
+
+    members: List[ArchiveMember] = []
+    try:
+        for raw_name, declared_size, read in iterate(file_path):
+            member_path = _member_path(raw_name)
+            if member_path is None:
+                logger.warning(f"Skipping archive member outside the archive: {file_path}!{raw_name}")
+                continue
+            members.append(
+                (
+                    member_path,
+                    _process_member(
+                        member_path,
+                        declared_size,
+                        read,
+                        max_file_size,
+                        include_binary,
+                        encoding,
+                        hashing_enabled,
+                        hash_encoding,
+                        binary_preview_bytes,
+                        classification_rules,
+                    ),
+                )
+            )
+    except (zipfile.BadZipFile, tarfile.TarError, EOFError, OSError) as e:
+        logger.warning(f"Could not expand archive {file_path}: {e}")
+        return None
+    return members
+
+
+def _process_member(
+    member_path: str,
+    declared_size: int,
+    read: Any,
+    max_file_size: int,
+    include_binary: bool,
+    encoding: Optional[str],
+    hashing_enabled: bool,
+    hash_encoding: Optional[str],
+    binary_preview_bytes: Optional[int],
+    classification_rules: ClassificationRules,
+) -> Dict[str, Any]:
+    if declared_size > max_file_size:
+        return {"type": "excluded", "reason": "file_size", "size": declared_size}
+    try:
+        # One byte more than allowed reveals members that understate their size.
+        data = read(max_file_size + 1)
+    except (zipfile.BadZipFile, tarfile.TarError, EOFError, OSError, RuntimeError) as e:
+        # RuntimeError covers encrypted zip members.
+        logger.error(f"Error reading archive member {member_path}: {e}")
+        return {
+            "type": "error",
+            "content": f"Errors during processing: {str(e)}",
+            "exception_type": type(e).__name__,
+            "exception_message": str(e),
+        }
+    if len(data) > max_file_size:
+        return {"type": "excluded", "reason": "file_size", "size": declared_size}
+
+    _, info = process_bytes(
+        data,
+        member_path,
+        max_file_size=max_file_size,
+        include_binary=include_binary,
+        encoding=encoding,
+        hashing_enabled=hashing_enabled,
+        hash_encoding=hash_encoding,
+        binary_preview_bytes=binary_preview_bytes,
+        classification_rules=classification_rules,
+    )
+    return info
+
+
+__all__ = ["ARCHIVE_SUFFIXES", "ArchiveMember", "is_archive", "process_archive"]
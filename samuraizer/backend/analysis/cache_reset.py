@@ -0,0 +1,24 @@
+"""A single entry point for clearing every in-memory cache of the analysis."""
+
+from __future__ import annotations
+
+from ...utils.file_utils.mime_detection import clear_mime_cache
+from ..services.pattern_service import compile_regex
+from .hash_service import clear_hash_cache
+
+
+def reset_caches() -> None:
+    """Clear every process-global cache kept by the analysis.
+
+    This covers the MIME classification cache, the memoised file digests
+    and the compiled regex patterns, so a following scan starts from a
+    clean slate even if files changed within the mtime granularity. The
+    persistent cache database is not touched. Each cache clears under its
+    own lock, so this is safe to call while other threads are scanning.
+    """
+    clear_mime_cache()
+    clear_hash_cache()
+    compile_regex.cache_clear()
+
+
+__all__ = ["reset_caches"]
@@ -1,29 +1,76 @@
 import base64
+import codecs
+import errno
+import io
+import json
 import logging
 import os
+import re
+import stat as stat_module
+import threading
+import zlib
 from codecs import getincrementaldecoder
 from pathlib import Path
-from typing import Any, Dict, List, Optional, Set, Tuple, Union
+from typing import Any, BinaryIO, Dict, List, NamedTuple, Optional, Set, Tuple, Union
 from datetime import datetime, timezone
 from zoneinfo import ZoneInfo
 
 from samuraizer.backend.cache.cache_operations import get_cached_entry, set_cached_entry
 from samuraizer.backend.cache.connection_pool import get_connection_context, is_cache_disabled
 from samuraizer.backend.cache.cache_cleaner import clean_cache
-from ..analysis.hash_service import HashService
+from ..analysis.hash_service import HashService, IncrementalHasher
 from samuraizer.utils.encoding_utils import normalize_encoding_hint
-from ...utils.file_utils.mime_detection import is_binary
+from ...utils.file_utils.doc_hints import detect_doc_hint
+from ...utils.file_utils.file_helpers import special_file_kind
+from ...utils.file_utils.file_times import get_birthtime_ns
+from ...utils.file_utils.mime_detection import (
+    DEFAULT_RULES,
+    ClassificationRules,
+    classify_file,
+    is_binary_bytes,
+)
+from ...utils.file_utils.structured_preview import STRUCTURED_EXTENSIONS, parse_structured_content
 from ...config.timezone_service import TimezoneService
 
 import charset_normalizer
 
+try:  # pragma: no cover - platform dependent
+    import grp
+    import pwd
+except ImportError:  # pragma: no cover - not available on Windows
+    grp = None  # type: ignore[assignment]
+    pwd = None  # type: ignore[assignment]
+
+try:  # pragma: no cover - optional dependency (``pip install samuraizer[zstd]``)
+    import zstandard
+except ImportError:  # pragma: no cover - zstd previews are unavailable
+    zstandard = None  # type: ignore[assignment]
+
 _STREAM_READ_CHUNK_SIZE = 256 * 1024  # 256 KiB keeps memory usage low while remaining efficient
 _MAX_BINARY_CONTENT_BYTES = 3 * 1024 * 1024  # 3 MiB preview for binary files
 _MAX_TEXT_CONTENT_BYTES = 5 * 1024 * 1024  # 5 MiB preview for text files
 _ENCODING_SAMPLE_BYTES = 512 * 1024  # up to 512 KiB of data for encoding detection
+_ZSTD_MAGIC = b"\x28\xb5\x2f\xfd"
+# Control characters that do not occur in text (tab, line breaks, form feed,
+# backspace and escape are allowed); the first one marks a binary tail.
+_BINARY_TAIL_RE = re.compile("[\x00-\x07\x0e-\x1a\x1c-\x1f]")
 
 logger = logging.getLogger(__name__)
 
+# Error handler that behaves like ``replace`` but records that a replacement
+# happened. Decoding runs on a single thread per file, so a thread-local flag
+# is sufficient.
+_RECORDING_REPLACE_ERRORS = "samuraizer.record_replace"
+_decode_state = threading.local()
+
+
+def _record_replace(error: UnicodeError) -> Tuple[str, int]:
+    _decode_state.had_errors = True
+    return codecs.replace_errors(error)
+
+
+codecs.register_error(_RECORDING_REPLACE_ERRORS, _record_replace)
+
 def process_file(
     file_path: Path,
     max_file_size: int,
@@ -31,7 +78,141 @@ def process_file(
     image_extensions: Set[str],
     encoding: Optional[str] = None,
     hashing_enabled: bool = True,
+    verify_hash: bool = False,
+    binary_preview_bytes: Optional[int] = None,
+    binary_content: bool = True,
+    hash_encoding: Optional[str] = None,
+    quick_hash: bool = False,
+    parse_structured: bool = False,
+    encoding_sample_bytes: Optional[int] = None,
+    strict_decode: bool = False,
+    decompress: bool = False,
+    content_first: bool = False,
+    include_mime: bool = False,
+    compress_binary: bool = False,
+    doc_hint: bool = False,
+    include_xattrs: bool = False,
+    assume_text: bool = False,
+    preview_hash: bool = False,
+    hybrid_preview: bool = False,
+    line_count_only: bool = False,
+    reencode_to: Optional[str] = None,
+    single_pass_hash: bool = False,
+    size: Optional[int] = None,
+    mtime: Optional[float] = None,
+    classification_rules: ClassificationRules = DEFAULT_RULES,
 ) -> Tuple[str, Optional[Dict[str, Any]]]:
+    """Process a single file, consulting the cache when hashing is enabled.
+
+    With ``verify_hash`` a cached entry is only trusted after the file's
+    content hash has been recomputed and compared with the cached hash.  A
+    size match is still required, but the modification time is ignored, which
+    keeps the cache correct when mtimes are unreliable (e.g. after a fresh
+    checkout) at the cost of hashing every file.
+
+    Cached entries are only used when they were produced with the same
+    options that shape an entry (preview caps, decoding and classification
+    settings); otherwise the file is processed again and its row replaced.
+
+    ``binary_preview_bytes`` caps the base64 preview of binary files
+    independently of the text preview limit, while ``binary_content=False``
+    skips reading binary files altogether and only reports their metadata.
+
+    When ``hash_encoding`` is given (and hashing is enabled) the content hash
+    is reported as ``file_hash`` in that encoding.  The cache always stores
+    the hex digest, so switching encodings does not invalidate it.
+
+    ``quick_hash`` adds a cheap ``quick_hash`` over the size and the first
+    and last 64 KiB of the file, suitable as a pre-filter for deduplication.
+
+    ``preview_hash`` adds a ``preview_hash`` over exactly the data in
+    ``content`` (the UTF-8 encoded text, or the decoded binary preview), so
+    truncated previews can be cached by what was actually delivered. It uses
+    the ``file_hash`` algorithm and encoding; ``file_hash`` still covers the
+    whole file.
+
+    ``parse_structured`` adds the parsed text preview of JSON, TOML and YAML
+    files as ``parsed`` (or the failure as ``parse_error``).
+
+    ``encoding_sample_bytes`` sets how much of a text file is inspected to
+    detect its encoding (512 KiB by default).
+
+    With ``strict_decode`` text that is not valid in the detected (or given)
+    encoding yields an error entry with reason ``decode_error`` instead of
+    content with replacement characters.
+
+    With ``decompress`` zstd-compressed files are previewed through a
+    streaming decoder, so the preview limits apply to the decompressed data
+    and only as much of the file is decoded as the preview needs. Such
+    entries report ``"compression": "zstd"``. This requires the optional
+    ``zstandard`` package; without it compressed files are handled as binary.
+
+    ``content_first`` classifies files as text or binary by their content and
+    only falls back to the extension when the content is inconclusive.
+
+    ``include_mime`` records the MIME type found during classification as
+    ``mime_type``; files classified by extension get a type guessed from it.
+
+    ``compress_binary`` deflates binary previews before base64-encoding them
+    (``"encoding": "base64+deflate"``, readable with :func:`zlib.decompress`)
+    whenever that makes the payload smaller.
+
+    ``doc_hint`` inspects the first line of text previews for doctypes, XML
+    declarations, coding comments and shebangs and reports what it finds as
+    ``doc_hint`` (e.g. ``"html"`` or ``"python-coding: utf-8"``).
+
+    ``hybrid_preview`` is meant for text files with a binary payload after a
+    text header (e.g. PEM-wrapped DER): the text preview stops at the first
+    control byte or undecodable sequence and reports where that binary tail
+    starts as ``binary_tail_offset``, instead of decoding the payload into
+    replacement characters. Such previews are marked ``truncated``.
+
+    ``reencode_to`` adds the text preview encoded in that encoding (e.g.
+    ``"utf-8"``, or ``"utf-8-sig"`` to prefix a byte order mark) as base64
+    in ``content_bytes_b64``, together with ``content_bytes_encoding``, so
+    consumers get the same representation whatever the source encoding.
+    ``content`` is unchanged.
+
+    ``line_count_only`` reports text files as ``{"type": "text", "encoding":
+    ..., "line_count": n}`` without any content, counting lines while
+    streaming the whole file, and binary files by their metadata only. Such
+    entries bypass the cache, which holds full previews.
+
+    With ``single_pass_hash`` a file that is hashed for the cache is opened
+    only once: the content hash is fed from the bytes read for the preview,
+    and the rest of the file is then read from the same handle for the hash
+    alone. The hash still covers the whole file. Files whose content is not
+    read (e.g. excluded binaries or compressed files) are hashed as usual.
+
+    ``assume_text`` skips the binary classification and reads the file as
+    text, which is how files with an explicitly assigned ``encoding`` are
+    handled by :func:`generate_directory_chunks`.
+
+    ``classification_rules`` adjusts the text/binary classification (e.g.
+    magic-byte overrides or extra textual MIME types) for this call only; cached entries are keyed on
+    the rules, so entries classified under other rules are misses.
+
+    ``size`` and ``mtime`` (seconds since the epoch) let callers that already
+    statted the file skip the stat call made here; the file is only statted
+    for values that are not supplied. When both are given the metadata is
+    limited to ``size``, ``modified``, ``modified_ns`` and ``timezone``, the
+    other fields requiring a stat; special files are still recognised by
+    opening the file without blocking and checking the open descriptor.
+    When only one is given, the metadata reports it in place of the statted
+    value. Negative values raise ``ValueError``.
+
+    Zero-byte files are reported as ``{"type": "empty"}`` together with their
+    metadata, without being read, classified or cached.
+
+    FIFOs, sockets and device files are never opened; they are reported as
+    ``{"type": "special", "kind": ...}`` (``fifo``, ``socket``,
+    ``char_device`` or ``block_device``) together with their metadata.
+
+    ``include_xattrs`` adds the file's extended attributes (e.g. SELinux labels
+    or ``com.apple.quarantine``) as ``xattrs``, mapping each name to its
+    base64-encoded value. The field is absent on platforms without extended
+    attribute support.
+    """
     filename = file_path.name
     logger.debug(
         "Processing file: %s (hashing_enabled=%s)",
@@ -39,12 +220,22 @@ def process_file(
         hashing_enabled,
     )
 
-    cache_active = hashing_enabled and not is_cache_disabled()
+    cache_active = hashing_enabled and not is_cache_disabled() and not line_count_only
     if hashing_enabled and not cache_active:
         logger.debug("Cache disabled at runtime; skipping hashing")
 
+    if size is not None and size < 0:
+        raise ValueError(f"size must not be negative, got {size}")
+    if mtime is not None and mtime < 0:
+        raise ValueError(f"mtime must not be negative, got {mtime}")
+
     try:
-        stat = file_path.stat()
+        if size is not None and mtime is not None:
+            stat: Union[os.stat_result, _SuppliedStat] = _SuppliedStat(size, mtime)
+            special_kind = _opened_special_kind(file_path)
+        else:
+            stat = _with_supplied_values(file_path.stat(), size, mtime)
+            special_kind = special_file_kind(stat.st_mode)
         current_size = stat.st_size
         current_mtime = stat.st_mtime
     except OSError as e:
@@ -56,6 +247,14 @@ def process_file(
             "exception_message": str(e)
         }
 
+    file_info: Dict[str, Any]
+    if special_kind is not None:
+        # Reading a FIFO or a device could block forever or never end.
+        logger.debug(f"Not reading special file {file_path} ({special_kind})")
+        file_info = {"type": "special", "kind": special_kind}
+        _add_metadata(file_info, stat, file_path)
+        return filename, file_info
+
     if current_size > max_file_size:
         logger.info(f"File too large and will be excluded: {file_path} ({current_size} bytes)")
         return filename, {
@@ -64,10 +263,58 @@ def process_file(
             "size": current_size
         }
 
+    if current_size == 0:
+        # Nothing to classify or decode; an empty text entry would be misleading.
+        file_info = {"type": "empty"}
+        _add_metadata(file_info, stat, file_path)
+        empty_hash = (
+            HashService.compute_file_hash(file_path)
+            if hashing_enabled and hash_encoding is not None
+            else None
+        )
+        return filename, _with_extras(
+            file_path,
+            file_info,
+            empty_hash,
+            hash_encoding,
+            quick_hash,
+            include_xattrs,
+            preview_hash,
+            reencode_to,
+        )
+
     # Only check cache if caching is enabled
     cached_entry = None
     file_hash = None
+    hashing_reader: Optional[_HashingReader] = None
+    cache_options = None
     if cache_active:
+        cache_options = _cache_options_key(
+            content_limit=min(
+                max_file_size,
+                max(_MAX_TEXT_CONTENT_BYTES, binary_preview_bytes or _MAX_BINARY_CONTENT_BYTES),
+            ),
+            include_binary=include_binary,
+            image_extensions=sorted(image_extensions),
+            encoding=encoding,
+            binary_preview_bytes=binary_preview_bytes,
+            binary_content=binary_content,
+            parse_structured=parse_structured,
+            encoding_sample_bytes=encoding_sample_bytes,
+            strict_decode=strict_decode,
+            decompress=decompress,
+            content_first=content_first,
+            include_mime=include_mime,
+            compress_binary=compress_binary,
+            doc_hint=doc_hint,
+            assume_text=assume_text,
+            hybrid_preview=hybrid_preview,
+            magic_overrides=[
+                [prefix.hex(), binary] for prefix, binary in classification_rules.magic_overrides
+            ],
+            textual_prefixes=list(classification_rules.textual_prefixes),
+            textual_types=sorted(classification_rules.textual_types),
+        )
         logger.debug(f"Cache enabled, checking cache for file: {file_path}")
         # Check cache using xxHash
         with get_connection_context() as conn:
@@ -78,6 +325,10 @@ def process_file(
             else:
                 logger.warning("Connection is None despite hashing being enabled")
 
+        if cached_entry and cached_entry.get("options") != cache_options:
+            logger.debug(f"Cached entry for {file_path} was produced with other options")
+            cached_entry = None
+
         if cached_entry:
             cached_size = cached_entry.get("size")
             cached_mtime = cached_entry.get("mtime")
@@ -91,48 +342,220 @@ def process_file(
             )
 
             # Only use cache if size and mtime match
-            if cached_size == current_size and cached_mtime == current_mtime:
+            if not verify_hash and cached_size == current_size and cached_mtime == current_mtime:
                 logger.debug(f"Cache hit for file: {file_path}")
-                return filename, cached_entry.get("file_info")
+                return filename, _with_extras(
+                    file_path,
+                    cached_entry.get("file_info"),
+                    cached_entry.get("file_hash"),
+                    hash_encoding,
+                    quick_hash,
+                    include_xattrs,
+                    preview_hash,
+                    reencode_to,
+                )
 
-        # Compute hash for cache validation
-        logger.debug(f"Computing hash for file: {file_path}")
-        file_hash = HashService.compute_file_hash(file_path)
-        logger.debug(f"Computed hash: {file_hash}")
+            if verify_hash and cached_size == current_size:
+                logger.debug(f"Verifying cached hash for file: {file_path}")
+                # Bypass the in-memory digest cache, which trusts mtimes.
+                file_hash = HashService.compute_file_hash(file_path, use_cache=False)
+                cached_info = cached_entry.get("file_info")
+                if file_hash and file_hash == cached_entry.get("file_hash"):
+                    logger.debug(f"Cache hit (hash verified) for file: {file_path}")
+                    if cached_mtime != current_mtime and isinstance(cached_info, dict):
+                        # Content is unchanged but the metadata is stale.
+                        _add_metadata(cached_info, stat, file_path)
+                        _update_cache(
+                            file_path, file_hash, cached_info, current_size, current_mtime, cache_options
+                        )
+                    return filename, _with_extras(
+                        file_path,
+                        cached_info,
+                        file_hash,
+                        hash_encoding,
+                        quick_hash,
+                        include_xattrs,
+                        preview_hash,
+                        reencode_to,
+                    )
+                logger.debug(f"Cached hash mismatch for file: {file_path}")
+
+        if file_hash is None and single_pass_hash:
+            logger.debug(f"Hashing {file_path} while reading its content")
+            hashing_reader = _HashingReader()
+        elif file_hash is None:
+            # Compute hash for cache validation
+            logger.debug(f"Computing hash for file: {file_path}")
+            file_hash = HashService.compute_file_hash(file_path)
+            logger.debug(f"Computed hash: {file_hash}")
         if isinstance(file_hash, dict) and file_hash.get("type") == "error":
             return filename, file_hash
 
-    file_info = _process_file_content(file_path, include_binary, image_extensions, max_file_size, encoding)
+    file_info = _process_file_content(
+        file_path,
+        include_binary,
+        image_extensions,
+        max_file_size,
+        encoding,
+        binary_preview_bytes=binary_preview_bytes,
+        binary_content=binary_content,
+        parse_structured=parse_structured,
+        encoding_sample_bytes=encoding_sample_bytes,
+        strict_decode=strict_decode,
+        decompress=decompress,
+        content_first=content_first,
+        include_mime=include_mime,
+        compress_binary=compress_binary,
+        doc_hint=doc_hint,
+        assume_text=assume_text,
+        hybrid_preview=hybrid_preview,
+        line_count_only=line_count_only,
+        hashing_reader=hashing_reader,
+        classification_rules=classification_rules,
+    )
     if file_info.get("type") in ["error", "excluded"]:
         return filename, file_info
 
-    _add_metadata(file_info, stat)
+    if hashing_reader is not None:
+        file_hash = hashing_reader.digest or HashService.compute_file_hash(file_path)
+
+    _add_metadata(file_info, stat, file_path)
 
     # Update cache only if caching is enabled and we have a valid hash
     if cache_active and file_hash:
-        logger.debug(f"Attempting to update cache for file: {file_path}")
-        with get_connection_context() as conn:
-            if conn is not None:  # Only proceed if connection is available (cache enabled)
-                try:
-                    logger.debug(
-                        "Got valid connection, writing to cache. Hash: %s",
-                        file_hash,
-                    )
-                    set_cached_entry(
-                        conn,
-                        str(file_path.resolve()),
-                        file_hash,
-                        file_info,
-                        current_size,
-                        current_mtime,
-                    )
-                    logger.debug(f"Cache updated successfully for: {file_path}")
-                except Exception as e:
-                    logger.error(f"Failed to update cache for {file_path}: {e}", exc_info=True)
-            else:
-                logger.warning("Connection is None when trying to write to cache")
+        _update_cache(file_path, file_hash, file_info, current_size, current_mtime, cache_options)
 
-    return filename, file_info
+    if hash_encoding is not None and hashing_enabled and file_hash is None:
+        file_hash = HashService.compute_file_hash(file_path)
+
+    return filename, _with_extras(
+        file_path,
+        file_info,
+        file_hash,
+        hash_encoding,
+        quick_hash,
+        include_xattrs,
+        preview_hash,
+        reencode_to,
+    )
+
+def _with_extras(
+    file_path: Path,
+    file_info: Optional[Dict[str, Any]],
+    file_hash: Optional[str],
+    hash_encoding: Optional[str],
+    quick_hash: bool,
+    include_xattrs: bool = False,
+    preview_hash: bool = False,
+    reencode_to: Optional[str] = None,
+) -> Optional[Dict[str, Any]]:
+    """Return a copy of ``file_info`` carrying the requested hashes and attributes."""
+    if not isinstance(file_info, dict):
+        return file_info
+    extra: Dict[str, Any] = {}
+    if hash_encoding is not None and file_hash:
+        extra["file_hash"] = HashService.encode_digest(file_hash, hash_encoding)
+    if quick_hash:
+        extra["quick_hash"] = HashService.compute_quick_hash(file_path, hash_encoding or "hex")
+    if preview_hash:
+        preview = _preview_bytes(file_info)
+        if preview is not None:
+            extra["preview_hash"] = HashService.compute_bytes_hash(preview, hash_encoding or "hex")
+    if include_xattrs:
+        xattrs = _read_xattrs(file_path)
+        if xattrs is not None:
+            extra["xattrs"] = xattrs
+    if reencode_to is not None:
+        extra.update(_reencoded_content(file_info, reencode_to))
+    return {**file_info, **extra} if extra else file_info
+
+def _reencoded_content(file_info: Dict[str, Any], target: str) -> Dict[str, Any]:
+    """Return the text preview encoded as ``target``, for ``reencode_to``."""
+    content = file_info.get("content")
+    if file_info.get("type") != "text" or not isinstance(content, str):
+        return {}
+    # Characters the target cannot represent become its replacement character.
+    encoded = content.encode(target, errors="replace")
+    return {
+        "content_bytes_b64": base64.b64encode(encoded).decode('ascii'),
+        "content_bytes_encoding": target,
+    }
+
+def _preview_bytes(file_info: Dict[str, Any]) -> Optional[bytes]:
+    """Return the data carried in ``content``, or None for entries without a preview."""
+    content = file_info.get("content")
+    if not isinstance(content, str):
+        return None
+    if file_info.get("type") == "text":
+        return content.encode("utf-8")
+    if file_info.get("type") == "binary":
+        payload = base64.b64decode(content)
+        if file_info.get("encoding") == "base64+deflate":
+            payload = zlib.decompress(payload)
+        return payload
+    return None
+
+def _read_xattrs(file_path: Path) -> Optional[Dict[str, str]]:
+    """Return the extended attributes of ``file_path`` as base64 values.
+
+    Returns None where the platform does not expose extended attributes.
+    """
+    if not hasattr(os, "listxattr"):
+        return None
+    try:
+        names = os.listxattr(file_path)
+    except OSError as e:
+        logger.debug(f"Could not list extended attributes of {file_path}: {e}")
+        return None
+    xattrs: Dict[str, str] = {}
+    for name in sorted(names):
+        try:
+            value = os.getxattr(file_path, name)
+        except OSError as e:
+            logger.debug(f"Could not read extended attribute {name} of {file_path}: {e}")
+            continue
+        xattrs[name] = base64.b64encode(value).decode("ascii")
+    return xattrs
+
+def _cache_options_key(**options: Any) -> str:
+    """Fingerprint the options that shape a cached ``file_info``.
+
+    Entries cached under other options (e.g. a different preview cap) are
+    misses, so they are never returned with a stale preview.
+    """
+    encoded = json.dumps(options, sort_keys=True).encode("utf-8")
+    return HashService.compute_bytes_hash(encoded)
+
+def _update_cache(
+    file_path: Path,
+    file_hash: str,
+    file_info: Dict[str, Any],
+    size: int,
+    mtime: float,
+    options: Optional[str] = None,
+) -> None:
+    logger.debug(f"Attempting to update cache for file: {file_path}")
+    with get_connection_context() as conn:
+        if conn is not None:  # Only proceed if connection is available (cache enabled)
+            try:
+                logger.debug(
+                    "Got valid connection, writing to cache. Hash: %s",
+                    file_hash,
+                )
+                set_cached_entry(
+                    conn,
+                    str(file_path.resolve()),
+                    file_hash,
+                    file_info,
+                    size,
+                    mtime,
+                    options=options,
+                )
+                logger.debug(f"Cache updated successfully for: {file_path}")
+            except Exception as e:
+                logger.error(f"Failed to update cache for {file_path}: {e}", exc_info=True)
+        else:
+            logger.warning("Connection is None when trying to write to cache")
 
 def _process_file_content(
     file_path: Path,
@@ -140,24 +563,94 @@ def _process_file_content(
     image_extensions: Set[str],
     max_file_size: int,
     encoding: Optional[str],
+    binary_preview_bytes: Optional[int] = None,
+    binary_content: bool = True,
+    parse_structured: bool = False,
+    encoding_sample_bytes: Optional[int] = None,
+    strict_decode: bool = False,
+    decompress: bool = False,
+    content_first: bool = False,
+    include_mime: bool = False,
+    compress_binary: bool = False,
+    doc_hint: bool = False,
+    assume_text: bool = False,
+    hybrid_preview: bool = False,
+    line_count_only: bool = False,
+    hashing_reader: Optional["_HashingReader"] = None,
+    classification_rules: ClassificationRules = DEFAULT_RULES,
 ) -> Dict[str, Any]:
     file_extension = file_path.suffix.lower()
     is_image = file_extension in image_extensions
 
     try:
-        binary = is_binary(file_path)
+        if decompress and _detect_compression(file_path) == "zstd":
+            file_info = _read_zstd_file(
+                file_path,
+                include_binary,
+                max_file_size,
+                encoding,
+                binary_preview_bytes,
+                encoding_sample_bytes,
+                strict_decode,
+                content_first,
+                compress_binary,
+                classification_rules,
+            )
+            if include_mime:
+                file_info["mime_type"] = "application/zstd"
+            return file_info
+
+        if assume_text:
+            binary = False
+            mime_type = classify_file(file_path, content_first, True, rules=classification_rules)[1] if include_mime else None
+        else:
+            binary, mime_type = classify_file(file_path, content_first, include_mime, rules=classification_rules)
 
         if (binary or is_image) and not include_binary:
             logger.debug(f"Excluding {'binary' if binary else 'image'} file: {file_path}")
-            return {
+            file_info = {
                 "type": "excluded",
                 "reason": "binary_or_image"
             }
-
-        if binary:
-            return _read_binary_file(file_path, max_file_size)
+        elif binary:
+            file_info = _read_binary_file(
+                file_path,
+                max_file_size,
+                binary_preview_bytes,
+                include_content=binary_content and not line_count_only,
+                compress=compress_binary,
+                hashing_reader=hashing_reader,
+            )
+        elif line_count_only:
+            file_info = _count_text_lines(file_path, encoding, encoding_sample_bytes)
         else:
-            return _read_text_file(file_path, max_file_size, encoding)
+            file_info = _read_text_file(
+                file_path,
+                max_file_size,
+                encoding,
+                sample_bytes=encoding_sample_bytes,
+                strict=strict_decode,
+                hybrid=hybrid_preview,
+                hashing_reader=hashing_reader,
+            )
+            if (
+                parse_structured
+                and file_extension in STRUCTURED_EXTENSIONS
+                and file_info.get("type") == "text"
+            ):
+                try:
+                    file_info["parsed"] = parse_structured_content(file_path, file_info["content"])
+                except ValueError as e:
+                    logger.debug(f"Could not parse {file_path}: {e}")
+                    file_info["parse_error"] = str(e)
+            if doc_hint and file_info.get("type") == "text":
+                hint = detect_doc_hint(file_info["content"])
+                if hint is not None:
+                    file_info["doc_hint"] = hint
+
+        if mime_type is not None and file_info.get("type") != "error":
+            file_info["mime_type"] = mime_type
+        return file_info
 
     except PermissionError as e:
         logger.error(f"Permission denied when reading file: {file_path}")
@@ -190,8 +683,21 @@ def _process_file_content(
             "exception_message": str(e)
         }
 
-def _read_binary_file(file_path: Path, max_file_size: int) -> Dict[str, Any]:
-    """Read binary file content without exhausting memory."""
+def _read_binary_file(
+    file_path: Path,
+    max_file_size: int,
+    preview_bytes: Optional[int] = None,
+    include_content: bool = True,
+    compress: bool = False,
+    hashing_reader: Optional["_HashingReader"] = None,
+) -> Dict[str, Any]:
+    """Read binary file content without exhausting memory.
+
+    ``preview_bytes`` overrides the default binary preview cap of
+    ``_MAX_BINARY_CONTENT_BYTES``. When ``include_content`` is false the file
+    is not opened at all and only its size is reported. A ``hashing_reader``
+    hashes the whole file from the same handle.
+    """
 
     try:
         file_size = file_path.stat().st_size
@@ -203,31 +709,36 @@ def _read_binary_file(file_path: Path, max_file_size: int) -> Dict[str, Any]:
                 "size": file_size
             }
 
-        read_limit = min(max_file_size, _MAX_BINARY_CONTENT_BYTES)
+        if not include_content:
+            logger.debug(f"Omitting binary content for {file_path}")
+            return {
+                "type": "binary",
+                "content_omitted": True,
+                "size": file_size,
+            }
+
+        preview_cap = _MAX_BINARY_CONTENT_BYTES if preview_bytes is None else max(0, preview_bytes)
+        read_limit = min(max_file_size, preview_cap)
         preview_size = min(file_size, read_limit)
 
-        buffer = bytearray()
-        with open(file_path, 'rb') as f:
-            while len(buffer) < preview_size:
-                chunk = f.read(min(_STREAM_READ_CHUNK_SIZE, preview_size - len(buffer)))
-                if not chunk:
-                    break
-                buffer.extend(chunk)
-
-        content = base64.b64encode(bytes(buffer)).decode('ascii')
-        result: Dict[str, Any] = {
-            "type": "binary",
-            "content": content,
-            "encoding": "base64",
-            "preview_bytes": len(buffer)
-        }
+        with open(file_path, 'rb') as raw:
+            f = raw if hashing_reader is None else hashing_reader.attach(raw)
+            if compress:
+                buffer = _read_binary_stream(f, preview_size)
+            else:
+                content, bytes_read = _read_base64_stream(f, preview_size)
+            if hashing_reader is not None:
+                hashing_reader.finish()
 
-        if file_size > preview_size:
+        truncated = file_size > preview_size
+        if truncated:
             logger.debug(f"Binary file {file_path} truncated to {preview_size} bytes")
-            result["truncated"] = True
 
-        logger.debug(f"Included binary file: {file_path} ({len(buffer)} preview bytes)")
-        return result
+        if compress:
+            logger.debug(f"Included binary file: {file_path} ({len(buffer)} preview bytes)")
+            return _binary_info(buffer, truncated, compress)
+        logger.debug(f"Included binary file: {file_path} ({bytes_read} preview bytes)")
+        return _binary_result(content, "base64", bytes_read, truncated)
     except Exception as e:
         logger.error(f"Error reading binary file {file_path}: {e}")
         return {
@@ -237,57 +748,118 @@ def _read_binary_file(file_path: Path, max_file_size: int) -> Dict[str, Any]:
             "exception_message": str(e)
         }
 
-def _read_text_file(file_path: Path, max_file_size: int, encoding: Optional[str]) -> Dict[str, Any]:
-    try:
-        read_limit = min(max_file_size, _MAX_TEXT_CONTENT_BYTES)
+def _detect_compression(file_path: Path) -> Optional[str]:
+    """Return the compression format of ``file_path`` if it can be decoded."""
 
-        with open(file_path, 'rb') as f:
-            sample = f.read(min(read_limit, _ENCODING_SAMPLE_BYTES))
-
-            encoding_hint = normalize_encoding_hint(encoding)
-
-            if encoding_hint is None:
-                matches = charset_normalizer.from_bytes(sample)
-                best_match = matches.best()
-                if best_match and best_match.encoding:
-                    encoding_to_use = best_match.encoding
-                    logger.debug(f"Detected encoding '{encoding_to_use}' for file {file_path}")
-                else:
-                    encoding_to_use = 'utf-8'
-                    logger.warning(f"Could not detect encoding for {file_path}. Falling back to 'utf-8'.")
-            else:
-                encoding_to_use = encoding_hint
-                logger.debug(f"Using provided encoding '{encoding_hint}' for file {file_path}")
+    if zstandard is None:
+        return None
+    with open(file_path, 'rb') as f:
+        magic = f.read(len(_ZSTD_MAGIC))
+    return "zstd" if magic == _ZSTD_MAGIC else None
 
-            f.seek(0)
-            decoder = getincrementaldecoder(encoding_to_use)(errors='replace')
-            text_chunks: List[str] = []
-            bytes_read = 0
 
-            while bytes_read < read_limit:
-                chunk = f.read(min(_STREAM_READ_CHUNK_SIZE, read_limit - bytes_read))
-                if not chunk:
-                    break
-                bytes_read += len(chunk)
-                text_chunks.append(decoder.decode(chunk, final=False))
+def _read_zstd_file(
+    file_path: Path,
+    include_binary: bool,
+    max_file_size: int,
+    encoding: Optional[str],
+    binary_preview_bytes: Optional[int],
+    sample_bytes: Optional[int],
+    strict: bool,
+    content_first: bool = False,
+    compress_binary: bool = False,
+    classification_rules: ClassificationRules = DEFAULT_RULES,
+) -> Dict[str, Any]:
+    """Preview the decompressed content of a zstd file.
 
-            text_chunks.append(decoder.decode(b'', final=True))
-            content = ''.join(text_chunks)
+    Frames are decoded incrementally and decoding stops one byte past the
+    preview cap, so large archives are never decompressed in full.
+    """
 
-        logger.debug(f"Read text file: {file_path} with encoding {encoding_to_use}")
-        result: Dict[str, Any] = {
-            "type": "text",
-            "encoding": encoding_to_use,
-            "content": content,
-            "preview_bytes": bytes_read
+    read_limit = min(max_file_size, _MAX_TEXT_CONTENT_BYTES)
+    try:
+        with open(file_path, 'rb') as raw:
+            with zstandard.ZstdDecompressor().stream_reader(raw) as reader:
+                data = bytes(_read_binary_stream(reader, read_limit + 1))
+    except zstandard.ZstdError as e:
+        logger.warning(f"Could not decompress zstd file {file_path}: {e}")
+        return {
+            "type": "error",
+            "reason": "decompression_error",
+            "content": f"Failed to decompress zstd file: {str(e)}",
+            "exception_type": type(e).__name__,
+            "exception_message": str(e)
         }
 
+    truncated = len(data) > read_limit
+    data = data[:read_limit]
+    inner_name = file_path.stem if file_path.suffix.lower() == ".zst" else file_path.name
+
+    if is_binary_bytes(data, inner_name, content_first, classification_rules):
+        if not include_binary:
+            logger.debug(f"Excluding compressed binary file: {file_path}")
+            return {"type": "excluded", "reason": "binary_or_image"}
+        preview_cap = _MAX_BINARY_CONTENT_BYTES if binary_preview_bytes is None else max(0, binary_preview_bytes)
+        preview = data[:preview_cap]
+        file_info = _binary_info(preview, truncated or len(preview) < len(data), compress_binary)
+    else:
+        try:
+            content, encoding_to_use, bytes_read, had_errors = _read_text_stream(
+                io.BytesIO(data), read_limit, encoding, str(file_path), sample_bytes, strict
+            )
+        except UnicodeDecodeError as e:
+            logger.warning(f"Invalid {e.encoding} data in compressed file {file_path}: {e.reason}")
+            return {
+                "type": "error",
+                "reason": "decode_error",
+                "content": f"Failed to decode text file: {str(e)}",
+                "exception_type": type(e).__name__,
+                "exception_message": str(e)
+            }
+        file_info = _text_info(content, encoding_to_use, bytes_read, truncated, had_errors)
+
+    file_info["compression"] = "zstd"
+    return file_info
+
+
+def _read_text_file(
+    file_path: Path,
+    max_file_size: int,
+    encoding: Optional[str],
+    sample_bytes: Optional[int] = None,
+    strict: bool = False,
+    hybrid: bool = False,
+    hashing_reader: Optional["_HashingReader"] = None,
+) -> Dict[str, Any]:
+    try:
+        read_limit = min(max_file_size, _MAX_TEXT_CONTENT_BYTES)
+
+        with open(file_path, 'rb') as raw:
+            f = raw if hashing_reader is None else hashing_reader.attach(raw)
+            content, encoding_to_use, bytes_read, had_errors = _read_text_stream(
+                f, read_limit, encoding, str(file_path), sample_bytes, strict
+            )
+            if hashing_reader is not None:
+                hashing_reader.finish()
+
+        logger.debug(f"Read text file: {file_path} with encoding {encoding_to_use}")
         file_size = file_path.stat().st_size
-        if file_size > read_limit:
+        truncated = file_size > read_limit
+        if truncated:
             logger.debug(f"Text file {file_path} truncated to {read_limit} bytes")
-            result["truncated"] = True
 
-        return result
+        if hybrid:
+            return _hybrid_text_info(content, encoding_to_use, bytes_read, truncated, had_errors)
+        return _text_info(content, encoding_to_use, bytes_read, truncated, had_errors)
+    except UnicodeDecodeError as e:
+        logger.warning(f"Invalid {e.encoding} data in text file {file_path}: {e.reason}")
+        return {
+            "type": "error",
+            "reason": "decode_error",
+            "content": f"Failed to decode text file: {str(e)}",
+            "exception_type": type(e).__name__,
+            "exception_message": str(e)
+        }
     except Exception as e:
         logger.error(f"Error reading text file {file_path}: {e}")
         return {
@@ -297,7 +869,611 @@ def _read_text_file(file_path: Path, max_file_size: int, encoding: Optional[str]
             "exception_message": str(e)
         }
 
-def _add_metadata(file_info: Dict[str, Any], stat: os.stat_result) -> None:
+def read_text_preview_fd(
+    fd: int,
+    max_bytes: int,
+    encoding: Optional[str] = None,
+    sample_bytes: Optional[int] = None,
+    hybrid_preview: bool = False,
+    reencode_to: Optional[str] = None,
+    probe_truncation: bool = True,
+) -> Dict[str, Any]:
+    """
+    Reads a text preview from an open file descriptor (e.g. stdin or a pipe).
+
+    The descriptor is not closed and is read unbuffered from its current
+    offset, so it is left just past the preview. Non-seekable streams are
+    supported: the bytes sampled for encoding detection are decoded first
+    instead of rewinding the stream.
+
+    Args:
+        fd (int): The file descriptor to read from
+        max_bytes (int): Maximum number of bytes to include in the preview
+        encoding (Optional[str]): Encoding hint; detected from the data if None
+        sample_bytes (Optional[int]): Bytes inspected for encoding detection
+        hybrid_preview (bool): Stop at a binary tail, as described for
+            :func:`process_file`
+        reencode_to (Optional[str]): Also return the preview in this
+            encoding, as described for :func:`process_file`
+        probe_truncation (bool): Read one byte past the preview of a
+            non-seekable stream to tell whether it was truncated. That byte
+            is lost, so pass False when the stream is read further; a
+            preview that fills ``max_bytes`` then counts as truncated.
+
+    Returns:
+        Dict[str, Any]: The same structure as for text files on disk
+    """
+    source = f"fd {fd}"
+    try:
+        read_limit = min(max(0, max_bytes), _MAX_TEXT_CONTENT_BYTES)
+        with os.fdopen(fd, 'rb', buffering=0, closefd=False) as f:
+            content, encoding_to_use, bytes_read, had_errors = _read_text_stream(
+                f, read_limit, encoding, source, sample_bytes
+            )
+            truncated = _has_more_data(f, probe_truncation, bytes_read >= read_limit)
+
+        if hybrid_preview:
+            file_info = _hybrid_text_info(content, encoding_to_use, bytes_read, truncated, had_errors)
+        else:
+            file_info = _text_info(content, encoding_to_use, bytes_read, truncated, had_errors)
+        if reencode_to is not None:
+            file_info.update(_reencoded_content(file_info, reencode_to))
+        return file_info
+    except Exception as e:
+        logger.error(f"Error reading text from {source}: {e}")
+        return {
+            "type": "error",
+            "content": f"Failed to read text stream: {str(e)}",
+            "exception_type": type(e).__name__,
+            "exception_message": str(e)
+        }
+
+def read_text_lines(
+    path: Path,
+    max_lines: int,
+    max_bytes: int,
+    encoding: Optional[str] = None,
+) -> Dict[str, Any]:
+    """
+    Reads the start of a text file as a list of lines.
+
+    Lines are split on LF and CRLF and do not include their line break. At
+    most ``max_bytes`` bytes are decoded, so the last line of a preview cut
+    by that limit may be incomplete.
+
+    Args:
+        path (Path): The file to read
+        max_lines (int): Maximum number of lines to return
+        max_bytes (int): Maximum number of bytes to decode
+        encoding (Optional[str]): Encoding hint; detected from the data if None
+
+    Returns:
+        Dict[str, Any]: ``{"type": "text", "encoding": ..., "lines": [...],
+        "truncated": bool}``, where ``truncated`` is set when either limit
+        cut the file short, or an error entry if the file cannot be read
+    """
+    file_path = Path(path)
+    try:
+        read_limit = min(max(0, max_bytes), _MAX_TEXT_CONTENT_BYTES)
+        with open(file_path, 'rb') as f:
+            content, encoding_to_use, _, _ = _read_text_stream(
+                f, read_limit, encoding, str(file_path)
+            )
+            truncated = bool(f.read(1))
+    except Exception as e:
+        logger.error(f"Error reading text lines from {file_path}: {e}")
+        return {
+            "type": "error",
+            "content": f"Failed to read text file: {str(e)}",
+            "exception_type": type(e).__name__,
+            "exception_message": str(e)
+        }
+
+    lines = content.split("\n")
+    if lines[-1] == "":
+        lines.pop()
+    max_lines = max(0, max_lines)
+    if len(lines) > max_lines:
+        lines = lines[:max_lines]
+        truncated = True
+    return {
+        "type": "text",
+        "encoding": encoding_to_use,
+        "lines": [line[:-1] if line.endswith("\r") else line for line in lines],
+        "truncated": truncated,
+    }
+
+def read_binary_preview_fd(
+    fd: int,
+    preview_bytes: Optional[int] = None,
+    compress: bool = False,
+    probe_truncation: bool = True,
+) -> Dict[str, Any]:
+    """
+    Reads a base64 binary preview from an open file descriptor.
+
+    The descriptor is not closed and is read unbuffered and sequentially from
+    its current offset, so pipes work and the stream is left just past the
+    preview.
+
+    Args:
+        fd (int): The file descriptor to read from
+        preview_bytes (Optional[int]): Preview cap; defaults to the binary preview limit
+        compress (bool): Deflate the preview before encoding when that shrinks it
+        probe_truncation (bool): Probe a non-seekable stream for truncation,
+            as described for :func:`read_text_preview_fd`
+
+    Returns:
+        Dict[str, Any]: The same structure as for binary files on disk
+    """
+    source = f"fd {fd}"
+    try:
+        preview_size = _MAX_BINARY_CONTENT_BYTES if preview_bytes is None else max(0, preview_bytes)
+        with os.fdopen(fd, 'rb', buffering=0, closefd=False) as f:
+            if compress:
+                buffer = _read_binary_stream(f, preview_size)
+                truncated = _has_more_data(f, probe_truncation, len(buffer) >= preview_size)
+                return _binary_info(buffer, truncated, compress)
+            content, bytes_read = _read_base64_stream(f, preview_size)
+            truncated = _has_more_data(f, probe_truncation, bytes_read >= preview_size)
+
+        return _binary_result(content, "base64", bytes_read, truncated)
+    except Exception as e:
+        logger.error(f"Error reading binary data from {source}: {e}")
+        return {
+            "type": "error",
+            "content": f"Failed to read binary stream: {str(e)}",
+            "exception_type": type(e).__name__,
+            "exception_message": str(e)
+        }
+
+def read_binary_bytes(path: Path, max_bytes: int) -> Tuple[bytes, Dict[str, Any]]:
+    """
+    Reads the start of a file as raw bytes, for in-process consumers.
+
+    Unlike the base64 previews of :func:`process_file` and
+    :func:`read_binary_preview_fd`, which suit serialized output, the data is
+    returned as it is, so there is nothing to decode.
+
+    Args:
+        path (Path): The file to read
+        max_bytes (int): Maximum number of bytes to return
+
+    Returns:
+        Tuple[bytes, Dict[str, Any]]: The data and ``{"type": "binary",
+        "size": ..., "preview_bytes": ..., "truncated": bool}``, or empty
+        data and an error entry if the file cannot be read
+    """
+    file_path = Path(path)
+    try:
+        with open(file_path, 'rb') as f:
+            size = os.fstat(f.fileno()).st_size
+            data = bytes(_read_binary_stream(f, max(0, max_bytes)))
+            truncated = bool(f.read(1))
+    except Exception as e:
+        logger.error(f"Error reading binary data from {file_path}: {e}")
+        return b"", {
+            "type": "error",
+            "content": f"Failed to read binary file: {str(e)}",
+            "exception_type": type(e).__name__,
+            "exception_message": str(e)
+        }
+
+    return data, {
+        "type": "binary",
+        "size": size,
+        "preview_bytes": len(data),
+        "truncated": truncated,
+    }
+
+def process_bytes(
+    data: bytes,
+    name: str,
+    max_file_size: int = _MAX_TEXT_CONTENT_BYTES,
+    include_binary: bool = True,
+    encoding: Optional[str] = None,
+    hashing_enabled: bool = True,
+    hash_encoding: Optional[str] = None,
+    binary_preview_bytes: Optional[int] = None,
+    classification_rules: ClassificationRules = DEFAULT_RULES,
+) -> Tuple[str, Dict[str, Any]]:
+    """
+    Processes an in-memory buffer like :func:`process_file` processes a file.
+
+    This allows e.g. archive members to be analysed without extracting them.
+    The ``name`` is used for the extension-based classification shortcuts.
+    Timestamps and permissions are reported as None since there is no file.
+
+    Args:
+        data (bytes): The content to process
+        name (str): File name (or path) the content belongs to
+        max_file_size (int): Buffers larger than this are excluded
+        include_binary (bool): Whether binary content is included
+        encoding (Optional[str]): Encoding hint for text content
+        hashing_enabled (bool): Whether to report ``file_hash``
+        hash_encoding (Optional[str]): Encoding of ``file_hash`` (hex by default)
+        binary_preview_bytes (Optional[int]): Cap for the base64 binary preview
+        classification_rules (ClassificationRules): Rules adjusting the
+            text/binary classification
+
+    Returns:
+        Tuple[str, Dict[str, Any]]: The file name and its info object
+    """
+    filename = Path(name).name
+    size = len(data)
+    if size > max_file_size:
+        return filename, {"type": "excluded", "reason": "file_size", "size": size}
+
+    try:
+        if size == 0:
+            file_info = {"type": "empty"}
+        elif is_binary_bytes(data, name, rules=classification_rules):
+            if not include_binary:
+                return filename, {"type": "excluded", "reason": "binary_or_image"}
+            preview_cap = _MAX_BINARY_CONTENT_BYTES if binary_preview_bytes is None else max(0, binary_preview_bytes)
+            preview_size = min(size, max_file_size, preview_cap)
+            file_info = _binary_info(data[:preview_size], size > preview_size)
+        else:
+            read_limit = min(max_file_size, _MAX_TEXT_CONTENT_BYTES)
+            content, encoding_to_use, bytes_read, had_errors = _read_text_stream(
+                io.BytesIO(data), read_limit, encoding, name
+            )
+            file_info = _text_info(
+                content, encoding_to_use, bytes_read, size > read_limit, had_errors
+            )
+    except Exception as e:
+        logger.error(f"Error processing buffer {name}: {e}")
+        return filename, {
+            "type": "error",
+            "content": f"Errors during processing: {str(e)}",
+            "exception_type": type(e).__name__,
+            "exception_message": str(e)
+        }
+
+    file_info.update({
+        "size": size,
+        "created": None,
+        "modified": None,
+        "created_ns": None,
+        "modified_ns": None,
+        "permissions": None,
+        "is_symlink": False,
+    })
+    if hashing_enabled:
+        file_info["file_hash"] = HashService.compute_bytes_hash(data, hash_encoding or "hex")
+    return filename, file_info
+
+def _text_info(
+    content: str,
+    encoding: str,
+    bytes_read: int,
+    truncated: bool,
+    had_errors: bool = False,
+) -> Dict[str, Any]:
+    result: Dict[str, Any] = {
+        "type": "text",
+        "encoding": encoding,
+        "content": content,
+        "preview_bytes": bytes_read,
+        "line_ending": _detect_line_ending(content),
+        "had_decode_errors": had_errors,
+    }
+    if truncated:
+        result["truncated"] = True
+        result["content_range"] = [0, bytes_read]
+    result["final_newline"] = None if truncated else content.endswith("\n")
+    return result
+
+def _hybrid_text_info(
+    content: str,
+    encoding: str,
+    bytes_read: int,
+    truncated: bool,
+    had_errors: bool,
+) -> Dict[str, Any]:
+    tail = _BINARY_TAIL_RE.search(content)
+    tail_start = tail.start() if tail else len(content)
+    if had_errors:
+        # Without decode errors a U+FFFD is genuine text.
+        replacement = content.find("\ufffd", 0, tail_start)
+        if replacement != -1:
+            tail_start = replacement
+    if tail_start == len(content):
+        return _text_info(content, encoding, bytes_read, truncated, had_errors)
+
+    prefix = content[:tail_start]
+    # The prefix decoded cleanly, so re-encoding it yields its original bytes.
+    offset = len(prefix.encode(encoding))
+    file_info = _text_info(prefix, encoding, offset, True)
+    file_info["binary_tail_offset"] = offset
+    return file_info
+
+def _binary_info(
+    buffer: Union[bytes, bytearray],
+    truncated: bool,
+    compress: bool = False,
+) -> Dict[str, Any]:
+    payload: Union[bytes, bytearray] = buffer
+    encoding = "base64"
+    if compress:
+        deflated = zlib.compress(payload)
+        # Incompressible data would only grow, so it stays plain base64.
+        if len(deflated) < len(payload):
+            payload = deflated
+            encoding = "base64+deflate"
+    return _binary_result(
+        base64.b64encode(payload).decode('ascii'), encoding, len(buffer), truncated
+    )
+
+def _binary_result(content: str, encoding: str, preview_bytes: int, truncated: bool) -> Dict[str, Any]:
+    result: Dict[str, Any] = {
+        "type": "binary",
+        "content": content,
+        "encoding": encoding,
+        "preview_bytes": preview_bytes
+    }
+    if truncated:
+        result["truncated"] = True
+        result["content_range"] = [0, preview_bytes]
+    return result
+
+def _detect_line_ending(content: str) -> str:
+    """Classify the newline style of ``content`` as lf, crlf, cr, mixed or none."""
+    crlf = content.count("\r\n")
+    counts = {
+        "lf": content.count("\n") - crlf,
+        "crlf": crlf,
+        "cr": content.count("\r") - crlf,
+    }
+    present = [style for style, count in counts.items() if count]
+    if not present:
+        return "none"
+    return present[0] if len(present) == 1 else "mixed"
+
+def _read_binary_stream(f: BinaryIO, limit: int) -> bytearray:
+    buffer = bytearray()
+    while len(buffer) < limit:
+        chunk = f.read(min(_STREAM_READ_CHUNK_SIZE, limit - len(buffer)))
+        if not chunk:
+            break
+        buffer.extend(chunk)
+    return buffer
+
+def _read_base64_stream(f: BinaryIO, limit: int) -> Tuple[str, int]:
+    """Base64-encode up to ``limit`` bytes as they are read.
+
+    Each chunk is encoded right away (bytes that do not complete a 3-byte
+    group are carried into the next chunk), so the raw preview is never held
+    in full next to its encoding. The result is identical to encoding all
+    bytes at once. Returns the encoded text and the number of bytes read.
+    """
+    encoded = bytearray()
+    carry = b""
+    bytes_read = 0
+    while bytes_read < limit:
+        chunk = f.read(min(_STREAM_READ_CHUNK_SIZE, limit - bytes_read))
+        if not chunk:
+            break
+        bytes_read += len(chunk)
+        data = carry + chunk
+        aligned = len(data) - len(data) % 3
+        encoded += base64.b64encode(data[:aligned])
+        carry = data[aligned:]
+    encoded += base64.b64encode(carry)
+    return encoded.decode('ascii'), bytes_read
+
+def _choose_encoding(sample: bytes, encoding: Optional[str], source: str) -> str:
+    """Return the encoding hint, or the encoding detected from ``sample``."""
+    encoding_hint = normalize_encoding_hint(encoding)
+
+    if encoding_hint is not None:
+        logger.debug(f"Using provided encoding '{encoding_hint}' for {source}")
+        return encoding_hint
+
+    matches = charset_normalizer.from_bytes(sample)
+    best_match = matches.best()
+    if best_match and best_match.encoding:
+        logger.debug(f"Detected encoding '{best_match.encoding}' for {source}")
+        return best_match.encoding
+    logger.warning(f"Could not detect encoding for {source}. Falling back to 'utf-8'.")
+    return 'utf-8'
+
+def _count_text_lines(
+    file_path: Path,
+    encoding: Optional[str],
+    sample_bytes: Optional[int] = None,
+) -> Dict[str, Any]:
+    """Count the lines of a text file while streaming it.
+
+    Only one decoded chunk is held at a time, so the whole file is counted
+    however large it is. A final line without a line break counts as well.
+    """
+    sample_limit = _ENCODING_SAMPLE_BYTES if sample_bytes is None else max(1, sample_bytes)
+    with open(file_path, 'rb') as f:
+        encoding_to_use = _choose_encoding(f.read(sample_limit), encoding, str(file_path))
+        f.seek(0)
+        decoder = getincrementaldecoder(encoding_to_use)(errors='replace')
+        line_count = 0
+        last_char = ""
+        while True:
+            chunk = f.read(_STREAM_READ_CHUNK_SIZE)
+            text = decoder.decode(chunk, final=not chunk)
+            if text:
+                line_count += text.count("\n")
+                last_char = text[-1]
+            if not chunk:
+                break
+    if last_char and last_char != "\n":
+        line_count += 1
+    return {"type": "text", "encoding": encoding_to_use, "line_count": line_count}
+
+class _HashingReader:
+    """Feeds the bytes read from a binary file into its content hash.
+
+    Readers may rewind (encoding detection re-reads its sample), so each
+    byte is hashed once, in file order; seeking past the hashed data is not
+    supported. :meth:`finish` hashes the rest of the file from the same
+    handle and sets :attr:`digest`.
+    """
+
+    def __init__(self) -> None:
+        self._hasher = IncrementalHasher()
+        self._file: Optional[BinaryIO] = None
+        self._position = 0
+        self._hashed = 0
+        self.digest: Optional[str] = None
+
+    def attach(self, f: BinaryIO) -> "_HashingReader":
+        self._file = f
+        return self
+
+    def read(self, size: int = -1) -> bytes:
+        data = self._file.read(size)
+        end = self._position + len(data)
+        if end > self._hashed:
+            self._hasher.update(data[self._hashed - self._position:])
+            self._hashed = end
+        self._position = end
+        return data
+
+    def seekable(self) -> bool:
+        return True
+
+    def tell(self) -> int:
+        return self._position
+
+    def seek(self, offset: int, whence: int = io.SEEK_SET) -> int:
+        if whence != io.SEEK_SET or offset > self._hashed:
+            raise io.UnsupportedOperation("can only seek back into data already read")
+        self._position = self._file.seek(offset)
+        return self._position
+
+    def finish(self) -> None:
+        self.seek(self._hashed)
+        while self.read(_STREAM_READ_CHUNK_SIZE):
+            pass
+        self.digest = self._hasher.hexdigest()
+
+
+def _stream_position(f: BinaryIO) -> Optional[int]:
+    """Return the position of ``f``, or None if it cannot seek (pipes, sockets)."""
+    try:
+        return f.tell() if f.seekable() else None
+    except (OSError, io.UnsupportedOperation):
+        return None
+
+def _has_more_data(f: BinaryIO, probe: bool, filled: bool) -> bool:
+    """Return whether ``f`` holds data past what was read.
+
+    Seekable streams are probed and rewound, so nothing is consumed. On other
+    streams the probe consumes a byte and only runs when ``probe`` is set;
+    otherwise a read that ``filled`` its limit counts as truncated.
+    """
+    position = _stream_position(f)
+    if position is not None:
+        more = bool(f.read(1))
+        f.seek(position)
+        return more
+    return bool(f.read(1)) if probe else filled
+
+def _read_text_stream(
+    f: BinaryIO,
+    read_limit: int,
+    encoding: Optional[str],
+    source: str,
+    sample_bytes: Optional[int] = None,
+    strict: bool = False,
+) -> Tuple[str, str, int, bool]:
+    """Decode up to ``read_limit`` bytes.
+
+    Returns the content, the encoding used, the number of bytes read and
+    whether invalid byte sequences were replaced. With ``strict`` invalid
+    sequences raise ``UnicodeDecodeError`` instead of being replaced.
+    """
+    sample_limit = _ENCODING_SAMPLE_BYTES if sample_bytes is None else max(1, sample_bytes)
+    start = _stream_position(f)
+    sample = f.read(min(read_limit, sample_limit))
+    encoding_to_use = _choose_encoding(sample, encoding, source)
+
+    decoder = getincrementaldecoder(encoding_to_use)(
+        errors='strict' if strict else _RECORDING_REPLACE_ERRORS
+    )
+    _decode_state.had_errors = False
+    text_chunks: List[str] = []
+    bytes_read = 0
+
+    if start is not None:
+        f.seek(start)
+    else:
+        # Pipes and sockets cannot be rewound; continue after the sample.
+        bytes_read = len(sample)
+        text_chunks.append(decoder.decode(sample, final=False))
+
+    while bytes_read < read_limit:
+        chunk = f.read(min(_STREAM_READ_CHUNK_SIZE, read_limit - bytes_read))
+        if not chunk:
+            break
+        bytes_read += len(chunk)
+        text_chunks.append(decoder.decode(chunk, final=False))
+
+    if bytes_read < read_limit or not _has_more_data(f, False, True):
+        # A preview cut at the read limit may end inside a multi-byte
+        # sequence, which must neither be replaced nor count as an error;
+        # data that really ends there is flushed, even at the limit.
+        text_chunks.append(decoder.decode(b'', final=True))
+    return ''.join(text_chunks), encoding_to_use, bytes_read, _decode_state.had_errors
+
+def _with_supplied_values(
+    stat: os.stat_result, size: Optional[int], mtime: Optional[float]
+) -> os.stat_result:
+    """Return ``stat`` with the size and mtime a caller supplied, if any."""
+    if size is None and mtime is None:
+        return stat
+    values = list(stat)
+    fields = {name: getattr(stat, name) for name in dir(stat) if name.startswith("st_")}
+    if size is not None:
+        values[stat_module.ST_SIZE] = size
+        fields["st_size"] = size
+    if mtime is not None:
+        values[stat_module.ST_MTIME] = int(mtime)
+        fields.update(st_mtime=mtime, st_mtime_ns=round(mtime * 1_000_000_000))
+    return os.stat_result(values, fields)
+
+def _opened_special_kind(file_path: Path) -> Optional[str]:
+    """Return the special file kind of ``file_path`` without blocking on it.
+
+    The file is opened with ``O_NONBLOCK``, so a FIFO without a writer does
+    not hang, and the open descriptor is checked with ``fstat``. Sockets
+    cannot be opened and are recognised from a stat of the path instead.
+    """
+    flags = os.O_RDONLY | getattr(os, "O_NONBLOCK", 0) | getattr(os, "O_BINARY", 0)
+    try:
+        fd = os.open(file_path, flags)
+    except OSError as e:
+        if e.errno != errno.ENXIO:
+            raise
+        return special_file_kind(os.stat(file_path).st_mode)
+    try:
+        return special_file_kind(os.fstat(fd).st_mode)
+    finally:
+        os.close(fd)
+
+class _SuppliedStat(NamedTuple):
+    """The size and mtime a caller passed to :func:`process_file` instead of a stat."""
+
+    st_size: int
+    st_mtime: float
+
+    @property
+    def st_mtime_ns(self) -> int:
+        return round(self.st_mtime * 1_000_000_000)
+
+
+def _add_metadata(
+    file_info: Dict[str, Any],
+    stat: Union[os.stat_result, _SuppliedStat],
+    file_path: Optional[Path] = None,
+) -> None:
     """Add metadata to file info with proper timezone handling."""
     try:
         tz_service = TimezoneService()
@@ -305,10 +1481,23 @@ def _add_metadata(file_info: Dict[str, Any], stat: os.stat_result) -> None:
         target_tz = tz_service.get_timezone()
         use_utc = bool(tz_state.get("use_utc", False))
 
+        if isinstance(stat, _SuppliedStat):
+            # Everything else would need the stat call the caller saved.
+            modified_dt = datetime.fromtimestamp(stat.st_mtime, tz=timezone.utc)
+            if not use_utc:
+                modified_dt = modified_dt.astimezone(target_tz)
+            file_info.update({
+                "size": stat.st_size,
+                "modified": modified_dt.isoformat(),
+                "modified_ns": stat.st_mtime_ns,
+                "timezone": str(target_tz),
+            })
+            return
+
         # Convert timestamps to datetime objects with proper timezone
-        birthtime = getattr(stat, "st_birthtime", None)
-        if isinstance(birthtime, (int, float)):
-            created_dt = datetime.fromtimestamp(birthtime, tz=timezone.utc)
+        birthtime_ns = get_birthtime_ns(file_path, stat)
+        if birthtime_ns is not None:
+            created_dt = datetime.fromtimestamp(birthtime_ns / 1_000_000_000, tz=timezone.utc)
             if not use_utc:
                 created_dt = created_dt.astimezone(target_tz)
             created_ts = created_dt.isoformat()
@@ -324,8 +1513,43 @@ def _add_metadata(file_info: Dict[str, Any], stat: os.stat_result) -> None:
             "size": stat.st_size,
             "created": created_ts,
             "modified": modified_ts,
+            # Full-resolution integer timestamps for sub-second change detection.
+            "created_ns": birthtime_ns,
+            "modified_ns": stat.st_mtime_ns,
             "permissions": oct(stat.st_mode),
-            "timezone": str(target_tz)  # Include timezone information in metadata
+            "permissions_symbolic": (
+                stat_module.filemode(stat.st_mode) if os.name == "posix" else None
+            ),
+            "timezone": str(target_tz),  # Include timezone information in metadata
+            "is_symlink": _is_symlink(file_path),
         })
+        file_info.update(_ownership_metadata(stat))
     except Exception as e:
         logger.warning(f"Could not retrieve complete metadata: {e}")
+
+
+def _is_symlink(file_path: Optional[Path]) -> bool:
+    """Return whether ``file_path`` itself is a symbolic link (one ``lstat``)."""
+    if file_path is None:
+        return False
+    try:
+        return stat_module.S_ISLNK(file_path.lstat().st_mode)
+    except OSError:
+        return False
+
+
+def _ownership_metadata(stat: os.stat_result) -> Dict[str, Any]:
+    """Return uid/gid and, where resolvable, owner/group names (Unix only)."""
+    if pwd is None or grp is None:
+        return {}
+
+    ownership: Dict[str, Any] = {"uid": stat.st_uid, "gid": stat.st_gid}
+    try:
+        ownership["owner"] = pwd.getpwuid(stat.st_uid).pw_name
+    except KeyError:
+        logger.debug(f"No user name for uid {stat.st_uid}")
+    try:
+        ownership["group"] = grp.getgrgid(stat.st_gid).gr_name
+    except KeyError:
+        logger.debug(f"No group name for gid {stat.st_gid}")
+    return ownership
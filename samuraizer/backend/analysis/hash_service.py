@@ -2,11 +2,15 @@
 
 from __future__ import annotations
 
+import base64
 import importlib.util
 import logging
+import os
+import threading
+from collections import OrderedDict
 from dataclasses import dataclass
 from pathlib import Path
-from typing import Callable, Iterable, List, Optional, Protocol, Set
+from typing import Any, Callable, Dict, Iterable, List, Optional, Protocol, Set, Tuple
 
 from colorama import Fore, Style
 
@@ -156,34 +160,226 @@ def _build_default_registry() -> HashRegistry:
 
 _HASH_REGISTRY = _build_default_registry()
 
+_DEFAULT_CHUNK_SIZE = 64 * 1024
+_MIN_AVERAGE_CHUNK_SIZE = 256
+_MASK64 = (1 << 64) - 1
+
+
+def _build_gear_table() -> List[int]:
+    import hashlib
+
+    # A fixed pseudo-random table keeps chunk boundaries stable across runs.
+    return [
+        int.from_bytes(hashlib.blake2b(bytes([value]), digest_size=8).digest(), "big")
+        for value in range(256)
+    ]
+
+
+_GEAR = _build_gear_table()
+
+
+def _fastcdc_cut(data: bytes, min_size: int, avg_size: int, max_size: int) -> int:
+    """Return the length of the next content-defined chunk at the start of ``data``."""
+
+    length = len(data)
+    if length <= min_size:
+        return length
+    length = min(length, max_size)
+    normal = min(avg_size, length)
+    bits = avg_size.bit_length() - 1
+    # Normalised chunking: a stricter mask before the average size and a
+    # looser one after it pulls chunk sizes towards the average.
+    mask_strict = (1 << (bits + 1)) - 1
+    mask_loose = (1 << (bits - 1)) - 1
+
+    fingerprint = 0
+    index = min_size
+    while index < normal:
+        fingerprint = ((fingerprint << 1) + _GEAR[data[index]]) & _MASK64
+        if not fingerprint & mask_strict:
+            return index + 1
+        index += 1
+    while index < length:
+        fingerprint = ((fingerprint << 1) + _GEAR[data[index]]) & _MASK64
+        if not fingerprint & mask_loose:
+            return index + 1
+        index += 1
+    return length
+
+
+_DEFAULT_HASH_CACHE_CAPACITY = 4096
+HashCacheKey = Tuple[str, int, int]
+
+
+class _DigestCache:
+    """Thread-safe LRU cache of hex digests keyed by ``(path, size, mtime_ns)``."""
+
+    def __init__(self, capacity: int) -> None:
+        self._capacity = max(0, capacity)
+        self._entries: "OrderedDict[HashCacheKey, str]" = OrderedDict()
+        self._lock = threading.Lock()
+
+    def get(self, key: HashCacheKey) -> Optional[str]:
+        with self._lock:
+            digest = self._entries.get(key)
+            if digest is not None:
+                self._entries.move_to_end(key)
+            return digest
+
+    def put(self, key: HashCacheKey, digest: str) -> None:
+        with self._lock:
+            if self._capacity == 0:
+                return
+            self._entries[key] = digest
+            self._entries.move_to_end(key)
+            while len(self._entries) > self._capacity:
+                self._entries.popitem(last=False)
+
+    def clear(self) -> None:
+        with self._lock:
+            self._entries.clear()
+
+    def configure(self, capacity: int) -> None:
+        with self._lock:
+            self._capacity = max(0, capacity)
+            while len(self._entries) > self._capacity:
+                self._entries.popitem(last=False)
+
+
+_DIGEST_CACHE = _DigestCache(_DEFAULT_HASH_CACHE_CAPACITY)
+
+
+def clear_hash_cache() -> None:
+    """Forget all digests memoised by :meth:`HashService.compute_file_hash`."""
+
+    _DIGEST_CACHE.clear()
+
+
+def configure_hash_cache(capacity: int) -> None:
+    """Set the number of memoised digests; ``0`` disables the in-memory cache."""
+
+    _DIGEST_CACHE.configure(capacity)
+
+
+def _cache_key(file_path: Path) -> Optional[HashCacheKey]:
+    try:
+        stat_result = file_path.stat()
+    except OSError:
+        return None
+    return (str(file_path.resolve()), stat_result.st_size, stat_result.st_mtime_ns)
+
+
+class IncrementalHasher:
+    """
+    Computes the digest of :meth:`HashService.compute_file_hash` from streamed data.
+
+    Feeding a file's bytes through :meth:`update`, in chunks of any size,
+    yields the same digest as hashing the file itself, so callers that
+    already read a file (e.g. for display) do not need to read it again.
+    The backend (xxHash or the blake2b fallback) is the one used for files.
+    """
+
+    def __init__(self) -> None:
+        self._hasher = _HASH_REGISTRY.create_hasher()
+
+    def update(self, data: bytes) -> None:
+        """Adds the next chunk of data to the digest."""
+        self._hasher.update(data)
+
+    def hexdigest(self) -> str:
+        """Returns the digest of the data seen so far as a lowercase hex string."""
+        return self._hasher.hexdigest()
+
+    def digest(self, encoding: str = "hex") -> str:
+        """
+        Returns the digest of the data seen so far.
+
+        Args:
+            encoding (str): Output encoding (``hex``, ``base64``, ``base64url`` or ``dec``)
+
+        Returns:
+            str: The digest in the requested encoding
+        """
+        return HashService.encode_digest(self.hexdigest(), encoding)
+
+
 class HashService:
     """Service for computing fast file hashes for cache validation."""
     
     CHUNK_SIZE = 65536  # Optimal chunk size for reading
-    
+    ENCODINGS = ("hex", "base64", "base64url", "dec")
+    QUICK_HASH_WINDOW = 64 * 1024
+
     @staticmethod
-    def compute_file_hash(file_path: Path) -> Optional[str]:
+    def encode_digest(hex_digest: str, encoding: str = "hex") -> str:
+        """
+        Re-encodes a hex digest as produced by :meth:`compute_file_hash`.
+
+        The digest bytes are interpreted big-endian, so the base64 and decimal
+        forms describe the same 64-bit value as the hex string.
+
+        Args:
+            hex_digest (str): The lowercase hex digest
+            encoding (str): One of ``hex``, ``base64``, ``base64url`` or ``dec``
+
+        Returns:
+            str: The digest in the requested encoding
+        """
+        if encoding == "hex":
+            return hex_digest
+        digest = bytes.fromhex(hex_digest)
+        if encoding == "base64":
+            return base64.b64encode(digest).decode("ascii")
+        if encoding == "base64url":
+            return base64.urlsafe_b64encode(digest).decode("ascii")
+        if encoding == "dec":
+            return str(int.from_bytes(digest, "big"))
+        raise ValueError(f"Unsupported hash encoding: {encoding}")
+
+    @staticmethod
+    def compute_file_hash(
+        file_path: Path,
+        encoding: str = "hex",
+        use_cache: bool = True,
+    ) -> Optional[str]:
         """
         Calculates a fast hash of a file for cache validation purposes.
         Prefers xxHash when available and transparently falls back to
         a portable hashlib implementation otherwise.
 
+        Digests are memoised in memory keyed by path, size and modification
+        time, so unchanged files are not re-read within the same process.
+
         Args:
             file_path (Path): The path to the file
+            encoding (str): Output encoding (``hex``, ``base64``, ``base64url`` or ``dec``)
+            use_cache (bool): Whether to consult the in-memory digest cache
 
         Returns:
-            Optional[str]: The file's hash as a hex string or None in case of errors
+            Optional[str]: The file's hash in the requested encoding or None in case of errors
         """
+        if encoding not in HashService.ENCODINGS:
+            raise ValueError(f"Unsupported hash encoding: {encoding}")
+
         if not file_path.exists():
             logger.warning(f"{Fore.YELLOW}File not found: {file_path}{Style.RESET_ALL}")
             return None
 
+        key = _cache_key(file_path) if use_cache else None
+        if key is not None:
+            cached = _DIGEST_CACHE.get(key)
+            if cached is not None:
+                return HashService.encode_digest(cached, encoding)
+
         try:
             hasher = _HASH_REGISTRY.create_hasher()
             with file_path.open('rb') as file:
                 for chunk in iter(lambda: file.read(HashService.CHUNK_SIZE), b""):
                     hasher.update(chunk)
-            return hasher.hexdigest()
+            digest = hasher.hexdigest()
+            if key is not None:
+                _DIGEST_CACHE.put(key, digest)
+            return HashService.encode_digest(digest, encoding)
 
         except PermissionError:
             logger.warning(f"{Fore.YELLOW}No permission to read the file: {file_path}{Style.RESET_ALL}")
@@ -194,5 +390,153 @@ class HashService:
         
         return None
 
+    @staticmethod
+    def compute_bytes_hash(data: bytes, encoding: str = "hex") -> str:
+        """
+        Calculates the same hash as :meth:`compute_file_hash` for an in-memory buffer.
+
+        Args:
+            data (bytes): The content to hash
+            encoding (str): Output encoding (``hex``, ``base64``, ``base64url`` or ``dec``)
+
+        Returns:
+            str: The hash in the requested encoding
+        """
+        if encoding not in HashService.ENCODINGS:
+            raise ValueError(f"Unsupported hash encoding: {encoding}")
+
+        hasher = _HASH_REGISTRY.create_hasher()
+        hasher.update(data)
+        return HashService.encode_digest(hasher.hexdigest(), encoding)
+
+    @staticmethod
+    def compute_quick_hash(
+        file_path: Path,
+        encoding: str = "hex",
+        window: int = QUICK_HASH_WINDOW,
+    ) -> Optional[str]:
+        """
+        Calculates a cheap "probably identical" hash over the file size and
+        the first and last ``window`` bytes, without reading the whole file.
+
+        Files with different quick hashes are guaranteed to differ; equal
+        quick hashes still require a full comparison.
+
+        Args:
+            file_path (Path): The path to the file
+            encoding (str): Output encoding (``hex``, ``base64``, ``base64url`` or ``dec``)
+            window (int): Number of bytes sampled at each end of the file
+
+        Returns:
+            Optional[str]: The quick hash in the requested encoding or None in case of errors
+        """
+        if encoding not in HashService.ENCODINGS:
+            raise ValueError(f"Unsupported hash encoding: {encoding}")
+
+        try:
+            hasher = _HASH_REGISTRY.create_hasher()
+            with file_path.open('rb') as file:
+                size = os.fstat(file.fileno()).st_size
+                hasher.update(size.to_bytes(8, "big"))
+                hasher.update(file.read(window))
+                if size > window:
+                    file.seek(max(window, size - window))
+                    hasher.update(file.read(window))
+            return HashService.encode_digest(hasher.hexdigest(), encoding)
+
+        except PermissionError:
+            logger.warning(f"{Fore.YELLOW}No permission to read the file: {file_path}{Style.RESET_ALL}")
+        except OSError as e:
+            logger.warning(f"{Fore.YELLOW}OS error when reading the file {file_path}: {e}{Style.RESET_ALL}")
+        except Exception as e:
+            logger.error(f"{Fore.RED}Unexpected error computing quick hash for {file_path}: {e}{Style.RESET_ALL}")
+
+        return None
+
+    @staticmethod
+    def compute_chunk_hashes(
+        file_path: Path,
+        avg_chunk_size: int = _DEFAULT_CHUNK_SIZE,
+        encoding: str = "hex",
+    ) -> Optional[List[Dict[str, Any]]]:
+        """
+        Splits a file into content-defined chunks (FastCDC) and hashes each one.
+
+        Chunk boundaries depend on the content rather than on fixed offsets,
+        so an insertion only changes the chunks around it. This allows delta
+        detection for large files. Chunks are between a quarter and eight
+        times ``avg_chunk_size`` long. Boundary detection runs in pure Python
+        and is considerably slower than hashing the whole file.
+
+        Args:
+            file_path (Path): The path to the file
+            avg_chunk_size (int): Target average chunk size in bytes (power of two recommended)
+            encoding (str): Output encoding (``hex``, ``base64``, ``base64url`` or ``dec``)
+
+        Returns:
+            Optional[List[Dict[str, Any]]]: ``offset``, ``length`` and ``hash`` per chunk,
+            or None in case of errors
+        """
+        if encoding not in HashService.ENCODINGS:
+            raise ValueError(f"Unsupported hash encoding: {encoding}")
+        if avg_chunk_size < _MIN_AVERAGE_CHUNK_SIZE:
+            raise ValueError(f"avg_chunk_size must be at least {_MIN_AVERAGE_CHUNK_SIZE} bytes")
+
+        min_size = avg_chunk_size // 4
+        max_size = avg_chunk_size * 8
+        chunks: List[Dict[str, Any]] = []
+        try:
+            with file_path.open('rb') as file:
+                buffer = bytearray()
+                offset = 0
+                eof = False
+                while True:
+                    while not eof and len(buffer) < max_size:
+                        data = file.read(max(HashService.CHUNK_SIZE, max_size - len(buffer)))
+                        if not data:
+                            eof = True
+                        buffer.extend(data)
+                    if not buffer:
+                        break
+                    cut = _fastcdc_cut(bytes(buffer[:max_size]), min_size, avg_chunk_size, max_size)
+                    hasher = _HASH_REGISTRY.create_hasher()
+                    hasher.update(bytes(buffer[:cut]))
+                    chunks.append({
+                        "offset": offset,
+                        "length": cut,
+                        "hash": HashService.encode_digest(hasher.hexdigest(), encoding),
+                    })
+                    offset += cut
+                    del buffer[:cut]
+            return chunks
+
+        except PermissionError:
+            logger.warning(f"{Fore.YELLOW}No permission to read the file: {file_path}{Style.RESET_ALL}")
+        except OSError as e:
+            logger.warning(f"{Fore.YELLOW}OS error when reading the file {file_path}: {e}{Style.RESET_ALL}")
+        except Exception as e:
+            logger.error(f"{Fore.RED}Unexpected error computing chunk hashes for {file_path}: {e}{Style.RESET_ALL}")
+
+        return None
+
+    @staticmethod
+    def compute_manifest_hash(entries: Iterable[Tuple[str, str]]) -> str:
+        """
+        Folds per-file hashes into a single digest for a whole tree.
+
+        Entries are sorted by path first, so the result does not depend on
+        the order in which files were processed.
+
+        Args:
+            entries (Iterable[Tuple[str, str]]): ``(relative path, file hash)`` pairs
+
+        Returns:
+            str: The manifest hash as a hex string
+        """
+        hasher = _HASH_REGISTRY.create_hasher()
+        for path, file_hash in sorted(entries):
+            hasher.update(f"{path}\0{file_hash}\n".encode("utf-8"))
+        return hasher.hexdigest()
+
 # Simple interface for backward compatibility if needed
 compute_file_hash = HashService.compute_file_hash
@@ -4,12 +4,15 @@ from __future__ import annotations
 
 import asyncio
 import os
+import queue as queue_module
 import threading
 from concurrent.futures import ThreadPoolExecutor, TimeoutError as FuturesTimeoutError
 from contextlib import suppress
 from functools import partial
-from typing import Any, AsyncGenerator, Dict
+from typing import Any, AsyncGenerator, Dict, Optional, Tuple
 
+from ...services.event_service.cancellation import CancellationTokenSource
+from .traversal_core import TraversalProgress
 from .traversal_processor import get_directory_structure
 from .traversal_stream import get_directory_structure_stream
 
@@ -32,7 +35,13 @@ async def get_directory_structure_stream_async(
     async_buffer_size: int = 256,
     **kwargs: Any,
 ) -> AsyncGenerator[Dict[str, Any], None]:
-    """Yield traversal payloads asynchronously by delegating to a thread."""
+    """Yield traversal payloads asynchronously by delegating to a thread.
+
+    ``async_buffer_size`` bounds the queue between the traversal thread and
+    the event loop (``SAMURAIZER_ASYNC_STREAM_CHUNK`` overrides it). Larger
+    buffers absorb slow consumers without stalling the traversal; smaller
+    ones keep fewer payloads in memory.
+    """
 
     env_override = os.getenv("SAMURAIZER_ASYNC_STREAM_CHUNK")
     if env_override:
@@ -118,3 +127,128 @@ async def get_directory_structure_stream_async(
             await producer_task
 
         await loop.run_in_executor(None, executor.shutdown, True)
+
+
+# Position of ``cancellation_token`` among the positional arguments of
+# :func:`get_directory_structure_stream`.
+_CANCELLATION_TOKEN_ARG = 11
+
+
+class TraversalPoller:
+    """Non-blocking access to a streaming traversal.
+
+    The traversal runs on a background thread; :meth:`poll` returns the next
+    payload if one is ready and :attr:`PENDING` otherwise, so an event loop
+    can ``await asyncio.sleep(...)`` between polls instead of blocking.
+    Once the traversal has finished :meth:`poll` returns :attr:`DONE`.
+
+    :meth:`approximate_remaining` offers a cheap progress estimate for
+    ETA displays without consuming the payloads, :meth:`current_file` names
+    the file processed last, and :meth:`summary` keeps the final summary
+    once it has been polled.
+
+    Unless the caller passes its own ``cancellation_token``, :meth:`close`
+    cancels the traversal, so the background thread stops processing files
+    instead of running on until the next payload is ready.
+    """
+
+    PENDING: object = object()
+    DONE: object = object()
+
+    def __init__(self, *args: Any, buffer_size: int = 256, **kwargs: Any) -> None:
+        self._queue: queue_module.Queue[Any] = queue_module.Queue(maxsize=max(1, buffer_size))
+        self._stop_event = threading.Event()
+        self._error: Optional[BaseException] = None
+        self._finished = False
+        self._progress = TraversalProgress()
+        self._summary: Optional[Dict[str, Any]] = None
+        self._cancellation = CancellationTokenSource()
+        kwargs["progress"] = self._progress
+        if len(args) > _CANCELLATION_TOKEN_ARG:
+            if args[_CANCELLATION_TOKEN_ARG] is None:
+                args = (
+                    *args[:_CANCELLATION_TOKEN_ARG],
+                    self._cancellation.token,
+                    *args[_CANCELLATION_TOKEN_ARG + 1:],
+                )
+        elif kwargs.get("cancellation_token") is None:
+            kwargs["cancellation_token"] = self._cancellation.token
+        self._thread = threading.Thread(
+            target=self._produce, args=args, kwargs=kwargs, name="samuraizer-poller", daemon=True
+        )
+        self._thread.start()
+
+    def _produce(self, *args: Any, **kwargs: Any) -> None:
+        try:
+            for payload in get_directory_structure_stream(*args, **kwargs):
+                while not self._stop_event.is_set():
+                    try:
+                        self._queue.put(payload, timeout=0.1)
+                        break
+                    except queue_module.Full:
+                        continue
+                if self._stop_event.is_set():
+                    break
+        except BaseException as exc:  # pragma: no cover - surfaced via poll()
+            self._error = exc
+        finally:
+            while not self._stop_event.is_set():
+                try:
+                    self._queue.put(self.DONE, timeout=0.1)
+                    break
+                except queue_module.Full:
+                    continue
+
+    def poll(self) -> Any:
+        """Return the next payload, :attr:`PENDING` or :attr:`DONE` without blocking."""
+
+        if self._finished:
+            return self.DONE
+        try:
+            item = self._queue.get_nowait()
+        except queue_module.Empty:
+            return self.PENDING
+        if item is self.DONE:
+            self._finished = True
+            if self._error is not None:
+                raise self._error
+        elif isinstance(item, dict) and "summary" in item:
+            self._summary = item["summary"]
+        return item
+
+    def summary(self) -> Optional[Dict[str, Any]]:
+        """Return the traversal summary, or ``None`` until it has been polled."""
+
+        return self._summary
+
+    def approximate_remaining(self) -> Optional[int]:
+        """Return the number of files still to be processed.
+
+        Returns ``None`` until gathering has finished, since the total is not
+        known before then.
+        """
+
+        return self._progress.approximate_remaining()
+
+    def current_file(self) -> Optional[Tuple[int, str]]:
+        """Return ``(index, relative_path)`` of the most recently processed file.
+
+        ``index`` is the file's position in gathering order. Returns ``None``
+        until the first file has been processed.
+        """
+
+        return self._progress.current
+
+    def close(self) -> None:
+        """Stop the background traversal and discard pending payloads."""
+
+        self._cancellation.cancel()
+        self._stop_event.set()
+        self._finished = True
+        self._thread.join(timeout=5)
+
+    def __enter__(self) -> "TraversalPoller":
+        return self
+
+    def __exit__(self, exc_type, exc, tb) -> None:
+        self.close()
@@ -4,11 +4,12 @@ from __future__ import annotations
 
 import json
 import os
-import sqlite3
 import tempfile
 from pathlib import Path
 from typing import Dict, Iterator, List, Optional, Sequence, Tuple
 
+from ...cache.connection_pool import cache_available, sqlite3
+
 
 class ProgressiveResultStore:
     """Persist traversal results to a temporary SQLite database.
@@ -19,6 +20,8 @@ class ProgressiveResultStore:
     """
 
     def __init__(self, *, prefix: str = "samuraizer_results", dir: Optional[Path] = None) -> None:
+        if not cache_available():
+            raise RuntimeError("Buffering results on disk requires the sqlite3 module")
         fd, path = tempfile.mkstemp(prefix=prefix, suffix=".db", dir=str(dir) if dir else None)
         os.close(fd)
         self._path = Path(path)
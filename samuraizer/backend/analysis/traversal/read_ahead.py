@@ -0,0 +1,116 @@
+"""Bounded read-ahead stage that overlaps file read latency with processing."""
+
+from __future__ import annotations
+
+import logging
+import os
+import stat
+import threading
+from concurrent.futures import Future, ThreadPoolExecutor
+from contextlib import nullcontext
+from pathlib import Path
+from typing import Any, Callable, Optional
+
+_READ_AHEAD_CHUNK_SIZE = 1024 * 1024
+
+
+def read_ahead(file_path: Path, limit: int) -> int:
+    """
+    Read up to ``limit`` bytes of a regular file and discard them.
+
+    This pulls the data into the operating system's page cache, so the
+    processing worker that reads the file next finds it there instead of
+    waiting on the storage. Files larger than ``limit`` and special files are
+    skipped; errors are left for processing to report.
+
+    Args:
+        file_path: The file to read
+        limit: Maximum number of bytes to read
+
+    Returns:
+        int: The number of bytes read
+    """
+    try:
+        st = os.stat(file_path)
+        if not stat.S_ISREG(st.st_mode) or st.st_size > limit:
+            return 0
+        bytes_read = 0
+        buffer = bytearray(max(1, min(st.st_size, _READ_AHEAD_CHUNK_SIZE)))
+        with open(file_path, "rb", buffering=0) as f:
+            while bytes_read < limit:
+                count = f.readinto(buffer)
+                if not count:
+                    break
+                bytes_read += count
+        return bytes_read
+    except OSError as e:
+        logging.debug("Read-ahead of %s failed: %s", file_path, e)
+        return 0
+
+
+class ReadAheadStage:
+    """Reads files on their own threads before handing them to the processing pool.
+
+    At most ``io_concurrency`` reads are in flight at once, independently of
+    the number of processing workers, so on high-latency storage (network or
+    object-storage mounts) many reads overlap while the CPU-bound hashing and
+    decoding stay on the processing pool. :meth:`submit` mirrors
+    :meth:`ThreadPoolExecutor.submit`; its future completes with the result
+    of the processing task, and cancelling it drops the file from both stages.
+    """
+
+    def __init__(
+        self,
+        io_concurrency: int,
+        limit: int,
+        processing: ThreadPoolExecutor,
+        open_files: Optional[threading.BoundedSemaphore] = None,
+    ) -> None:
+        if io_concurrency < 1:
+            raise ValueError("io_concurrency must be at least 1")
+        self._reads = ThreadPoolExecutor(
+            max_workers=io_concurrency, thread_name_prefix="samuraizer-read"
+        )
+        self._limit = limit
+        self._processing = processing
+        self._open_files = open_files
+
+    def submit(self, fn: Callable[..., Any], file_path: Path, *args: Any, **kwargs: Any) -> Future:
+        outer: Future = Future()
+
+        def _read_then_process() -> None:
+            if outer.cancelled():
+                return
+            # Reading a file opens it, so the read holds a descriptor slot too.
+            with self._open_files if self._open_files is not None else nullcontext():
+                read_ahead(file_path, self._limit)
+            if outer.cancelled():
+                return
+            try:
+                inner = self._processing.submit(fn, file_path, *args, **kwargs)
+            except RuntimeError as e:  # the processing pool was shut down
+                if outer.set_running_or_notify_cancel():
+                    outer.set_exception(e)
+                return
+            outer.add_done_callback(lambda done: inner.cancel() if done.cancelled() else None)
+            inner.add_done_callback(lambda done: _forward(done, outer))
+
+        self._reads.submit(_read_then_process)
+        return outer
+
+    def shutdown(self) -> None:
+        """Stop reading; reads not yet started are dropped."""
+        self._reads.shutdown(wait=True, cancel_futures=True)
+
+
+def _forward(inner: Future, outer: Future) -> None:
+    if inner.cancelled():
+        outer.cancel()
+        return
+    if not outer.set_running_or_notify_cancel():
+        return
+    exception = inner.exception()
+    if exception is not None:
+        outer.set_exception(exception)
+    else:
+        outer.set_result(inner.result())
@@ -1,11 +1,16 @@
-from dataclasses import dataclass
-from pathlib import Path
-from typing import Iterator, Optional, Set, Tuple, List
+from concurrent.futures import Future, ThreadPoolExecutor
+from dataclasses import dataclass, field
+from pathlib import Path, PurePosixPath
+from typing import Dict, Iterator, Optional, Sequence, Set, Tuple, List
 import logging
+import stat
 from colorama import Fore, Style
 
-from ....backend.services.pattern_service import matches_patterns
+from ....backend.services.dockerignore_service import DockerIgnore
+from ....backend.services.ignore_service import IgnoreLayer, is_ignored, load_ignore_layer
+from ....backend.services.pattern_service import CompiledFilters
 from ...services.event_service.cancellation import CancellationToken
+from ....utils.file_utils.file_helpers import special_file_kind
 
 
 @dataclass
@@ -14,6 +19,109 @@ class TraversalCounters:
 
     included: int = 0
     excluded: int = 0
+    escaped_symlinks: List[Dict[str, str]] = field(default_factory=list)
+    walk_errors: List[Dict[str, str]] = field(default_factory=list)
+    excluded_reasons: Dict[str, int] = field(default_factory=dict)
+    # Directories left without files or subdirectories once exclusions apply.
+    empty_dirs: List[Path] = field(default_factory=list)
+
+    @property
+    def excluded_by_name(self) -> int:
+        """Files excluded because their name is listed in ``excluded_files``."""
+
+        return self.excluded_reasons.get("file_name", 0)
+
+    @property
+    def excluded_by_pattern(self) -> int:
+        """Files excluded because they matched an exclusion pattern."""
+
+        return self.excluded_reasons.get("pattern", 0)
+
+    def record_walk_error(self, path: Path, error: Exception) -> None:
+        """Remember a directory that could not be read or resolved."""
+
+        self.walk_errors.append({"path": str(path), "error": str(error)})
+
+    def record_exclusion(self, reason: str) -> None:
+        """Count an excluded file and attribute it to ``reason``."""
+
+        self.excluded += 1
+        self.excluded_reasons[reason] = self.excluded_reasons.get(reason, 0) + 1
+
+
+class RootNotFoundError(FileNotFoundError):
+    """Raised when a traversal root does not exist or is not a directory."""
+
+
+class TraversalAbortedError(RuntimeError):
+    """Raised when ``on_error="abort"`` stops a traversal at a failed file."""
+
+    def __init__(self, path: Path, info: Dict[str, object]) -> None:
+        self.path = path
+        self.info = info
+        message = info.get("content") or info.get("reason") or "unknown error"
+        super().__init__(f"Processing {path} failed: {message}")
+
+
+@dataclass
+class TraversalProgress:
+    """Live progress shared with consumers that poll a running traversal.
+
+    ``included`` stays ``None`` until gathering has finished, because the
+    number of files to process is not known before that.
+
+    ``current`` holds the ``(index, relative_path)`` of the most recently
+    processed file, where ``index`` is the file's position in gathering
+    order. Workers finish out of order, so the index tells consumers how far
+    along the reported file really is. It is replaced as a whole so readers
+    never see an index paired with another file's path.
+    """
+
+    processed: int = 0
+    included: Optional[int] = None
+    current: Optional[Tuple[int, str]] = None
+
+    def approximate_remaining(self) -> Optional[int]:
+        """Files still to be processed, or ``None`` while gathering is running."""
+
+        if self.included is None:
+            return None
+        return max(0, self.included - self.processed)
+
+
+_FILE_ATTRIBUTE_HIDDEN = getattr(stat, "FILE_ATTRIBUTE_HIDDEN", 0x2)
+
+
+def is_hidden(entry: Path) -> bool:
+    """Return ``True`` for dotfiles and, on Windows, entries with the hidden attribute."""
+
+    if entry.name.startswith("."):
+        return True
+    try:
+        attributes = getattr(entry.lstat(), "st_file_attributes", 0)
+    except OSError:
+        return False
+    return bool(attributes & _FILE_ATTRIBUTE_HIDDEN)
+
+
+def is_special_file(entry: Path) -> bool:
+    """Return ``True`` for FIFOs, sockets and device files (following symlinks)."""
+
+    try:
+        return special_file_kind(entry.stat().st_mode) is not None
+    except OSError:
+        return False
+
+
+def _prefix_parts(path_prefix: Optional[str]) -> Tuple[str, ...]:
+    """Split a ``path_prefix`` into its components, rejecting escapes from the root."""
+
+    if not path_prefix:
+        return ()
+    prefix = PurePosixPath(path_prefix.replace("\\", "/"))
+    if prefix.is_absolute() or ".." in prefix.parts:
+        raise ValueError(f"path_prefix must be relative to the root: {path_prefix}")
+    return tuple(part for part in prefix.parts if part != ".")
 
 
 def traverse_and_collect(
@@ -23,6 +131,15 @@ def traverse_and_collect(
     exclude_patterns: List[str],
     follow_symlinks: bool,
     cancellation_token: Optional[CancellationToken] = None,
+    follow_file_symlinks: bool = False,
+    ignore_files: Optional[List[str]] = None,
+    skip_hidden: bool = False,
+    dockerignore_path: Optional[Path] = None,
+    counters: Optional[TraversalCounters] = None,
+    filters: Optional[CompiledFilters] = None,
+    strict_patterns: bool = False,
+    gather_threads: int = 1,
+    path_prefix: Optional[str] = None,
 ) -> Tuple[Iterator[Path], TraversalCounters]:
     """Yield files found during traversal while tracking statistics.
 
@@ -31,20 +148,150 @@ def traverse_and_collect(
     instance with live statistics.  This avoids materialising the full list of
     files in memory and enables progressive processing of very large
     repositories.
+
+    When ``follow_file_symlinks`` is enabled, symbolic links are handled
+    separately from regular entries: linked files are resolved and yielded
+    only if their target stays inside ``root_dir`` (escapes are recorded in
+    ``counters.escaped_symlinks``), while linked directories are descended
+    only when ``follow_symlinks`` is set as well.
+
+    ``ignore_files`` names per-directory ignore files (e.g. ``.gitignore`` or
+    ``.samuraizerignore``) whose gitignore-style rules apply to the directory
+    they live in and all of its descendants.
+
+    ``skip_hidden`` prunes hidden directories and excludes hidden files with
+    the reason ``"hidden"``.  Names starting with ``.`` are always hidden; on
+    Windows the hidden file attribute is honoured as well.
+
+    ``dockerignore_path`` points at a ``.dockerignore`` file whose rules are
+    matched against paths relative to ``root_dir`` using Docker's semantics
+    (see :class:`DockerIgnore`); excluded files use the reason
+    ``"dockerignore"``.
+
+    FIFOs, sockets and device files are yielded like regular files, so they
+    are reported instead of silently dropped; they must never be read.
+
+    Directories below ``root_dir`` that end up without any file or
+    subdirectory once the exclusions apply are recorded in
+    ``counters.empty_dirs``.
+
+    Directories that cannot be read are skipped, but recorded in
+    ``counters.walk_errors`` so that inaccessible parts of the tree are not
+    mistaken for empty ones.
+
+    A prebuilt :class:`CompiledFilters` passed as ``filters`` replaces
+    ``excluded_folders``, ``excluded_files`` and ``exclude_patterns``, so
+    repeated traversals do not recompile the same patterns. With
+    ``strict_patterns`` an invalid pattern raises ``ValueError`` instead of
+    being skipped.
+
+    With ``gather_threads`` above one, directories are listed ahead of time
+    on that many threads as soon as they are discovered, which hides the
+    latency of slow (e.g. network) file systems. Files are still yielded in
+    the same order as with a single thread.
+
+    ``path_prefix`` (relative to ``root_dir``, e.g. ``"src/app"``) restricts
+    the walk to that subtree: only the directories leading to it are listed
+    and sibling directories are never entered, while yielded paths stay
+    relative to ``root_dir`` as in a full walk. Files outside the subtree are
+    not counted as excluded. An absolute prefix or one containing ``..``
+    raises ``ValueError``.
+
+    Passing ``counters`` accumulates statistics into an existing instance,
+    which is how :func:`traverse_roots` aggregates several roots.
+
+    Raises:
+        RootNotFoundError: If ``root_dir`` does not exist or is not a directory.
     """
 
-    counters = TraversalCounters()
+    _check_root(root_dir)
+
+    prefix_parts = _prefix_parts(path_prefix)
+
+    if counters is None:
+        counters = TraversalCounters()
+    if filters is None:
+        filters = CompiledFilters(
+            excluded_folders, excluded_files, exclude_patterns, strict=strict_patterns
+        )
     visited_paths: Set[Path] = set()
+    resolved_root: Optional[Path] = None
+    if follow_file_symlinks:
+        try:
+            resolved_root = root_dir.resolve()
+        except Exception as e:
+            logging.error(
+                f"{Fore.RED}Error when resolving {root_dir}: {e}{Style.RESET_ALL}"
+            )
+            resolved_root = root_dir
+
+    def _resolve_file_symlink(entry: Path) -> Optional[Path]:
+        """Return the target of ``entry`` when it may be processed."""
+
+        try:
+            target = entry.resolve(strict=True)
+        except (OSError, RuntimeError) as e:
+            logging.warning(
+                f"{Fore.YELLOW}Could not resolve symbolic link {entry}: {e}{Style.RESET_ALL}"
+            )
+            return None
+
+        try:
+            target.relative_to(resolved_root or root_dir)
+        except ValueError:
+            logging.warning(
+                f"{Fore.YELLOW}Symbolic link escapes the root directory: "
+                f"{entry} -> {target}{Style.RESET_ALL}"
+            )
+            counters.escaped_symlinks.append({"file": str(entry), "target": str(target)})
+            counters.record_exclusion("symlink_escape")
+            return None
+        return target
+
+    ignore_file_names = list(ignore_files or [])
+    dockerignore = DockerIgnore.from_file(Path(dockerignore_path)) if dockerignore_path else None
+
+    def _context_path(entry: Path) -> str:
+        return entry.relative_to(root_dir).as_posix()
 
     def _iterator() -> Iterator[Path]:
-        stack = [root_dir]
+        executor = ThreadPoolExecutor(max_workers=gather_threads) if gather_threads > 1 else None
+        try:
+            yield from _walk(executor)
+        finally:
+            if executor is not None:
+                executor.shutdown(wait=False, cancel_futures=True)
+
+    def _prefetch(
+        executor: Optional[ThreadPoolExecutor], directory: Path
+    ) -> Optional[Future[List[Path]]]:
+        if executor is None:
+            return None
+        return executor.submit(lambda: list(directory.iterdir()))
+
+    def _inside_prefix(directory: Path) -> bool:
+        # Directories leading to the prefix only look empty.
+        return len(directory.relative_to(root_dir).parts) >= len(prefix_parts)
+
+    def _within_prefix(entry: Path) -> bool:
+        # Directories leading to the prefix are entered; everything else
+        # has to lie inside it.
+        parts = entry.relative_to(root_dir).parts
+        if len(parts) >= len(prefix_parts):
+            return parts[: len(prefix_parts)] == prefix_parts
+        return parts == prefix_parts[: len(parts)] and entry.is_dir()
+
+    def _walk(executor: Optional[ThreadPoolExecutor]) -> Iterator[Path]:
+        stack: List[Tuple[Path, Tuple[IgnoreLayer, ...], Optional[Future[List[Path]]]]] = [
+            (root_dir, (), None)
+        ]
 
         while stack:
             if cancellation_token and cancellation_token.is_cancellation_requested():
                 logging.debug("Traversal aborted due to cancellation request.")
                 break
 
-            current_dir = stack.pop()
+            current_dir, ignore_layers, listing = stack.pop()
             try:
                 if follow_symlinks:
                     resolved_dir = current_dir.resolve()
@@ -58,43 +305,135 @@ def traverse_and_collect(
                 logging.error(
                     f"{Fore.RED}Error when resolving {current_dir}: {e}{Style.RESET_ALL}"
                 )
+                counters.record_walk_error(current_dir, e)
                 continue
 
+            if ignore_file_names:
+                patterns = load_ignore_layer(current_dir, ignore_file_names)
+                if patterns:
+                    ignore_layers = ignore_layers + ((current_dir, patterns),)
+
+            has_children = False
             try:
-                for entry in current_dir.iterdir():
+                entries = listing.result() if listing is not None else current_dir.iterdir()
+                for entry in entries:
                     if cancellation_token and cancellation_token.is_cancellation_requested():
                         logging.debug("Traversal aborted due to cancellation request.")
                         break
 
+                    if follow_file_symlinks and entry.is_symlink():
+                        if entry.is_dir():
+                            if not follow_symlinks:
+                                logging.debug(
+                                    f"{Fore.CYAN}Skip linked folder: {entry}{Style.RESET_ALL}"
+                                )
+                                continue
+                        elif entry.is_file():
+                            if _resolve_file_symlink(entry) is None:
+                                continue
+                        else:
+                            continue
+
+                    if prefix_parts and not _within_prefix(entry):
+                        continue
+
                     if entry.is_dir():
                         if (
-                            entry.name in excluded_folders
-                            or matches_patterns(entry.name, exclude_patterns)
+                            (skip_hidden and is_hidden(entry))
+                            or filters.excludes_folder(entry.name)
+                            or (ignore_layers and is_ignored(ignore_layers, entry, True))
+                            or (dockerignore and dockerignore.prunes_directory(_context_path(entry)))
                         ):
                             logging.debug(
                                 f"{Fore.CYAN}Exclude folders: {entry}{Style.RESET_ALL}"
                             )
                             continue
-                        stack.append(entry)
-                    elif entry.is_file():
-                        if (
-                            entry.name in excluded_files
-                            or matches_patterns(entry.name, exclude_patterns)
-                        ):
+                        has_children = True
+                        stack.append((entry, ignore_layers, _prefetch(executor, entry)))
+                    elif entry.is_file() or is_special_file(entry):
+                        reason: Optional[str] = None
+                        if skip_hidden and is_hidden(entry):
+                            reason = "hidden"
+                        else:
+                            reason = filters.file_exclusion_reason(entry.name)
+                        if reason is None:
+                            if ignore_layers and is_ignored(ignore_layers, entry, False):
+                                reason = "ignore_file"
+                            elif dockerignore and dockerignore.matches(_context_path(entry)):
+                                reason = "dockerignore"
+                        if reason is not None:
                             logging.debug(
                                 f"{Fore.YELLOW}Exclude file: {entry}{Style.RESET_ALL}"
                             )
-                            counters.excluded += 1
+                            counters.record_exclusion(reason)
                             continue
+                        has_children = True
                         counters.included += 1
                         yield entry
+                else:
+                    if (
+                        not has_children
+                        and current_dir != root_dir
+                        and _inside_prefix(current_dir)
+                    ):
+                        counters.empty_dirs.append(current_dir)
             except PermissionError as e:
                 logging.warning(
                     f"{Fore.YELLOW}Could not read directory: {current_dir} - {e}{Style.RESET_ALL}"
                 )
+                counters.record_walk_error(current_dir, e)
             except Exception as e:
                 logging.error(
                     f"{Fore.RED}Errors when passing through {current_dir}: {e}{Style.RESET_ALL}"
                 )
+                counters.record_walk_error(current_dir, e)
+
+    return _iterator(), counters
+
+
+def _check_root(root_dir: Path) -> None:
+    if not root_dir.is_dir():
+        problem = "is not a directory" if root_dir.exists() else "does not exist"
+        raise RootNotFoundError(f"Root directory {root_dir} {problem}")
+
+
+def traverse_roots(
+    roots: Sequence[Path],
+    excluded_folders: Set[str],
+    excluded_files: Set[str],
+    exclude_patterns: List[str],
+    follow_symlinks: bool,
+    cancellation_token: Optional[CancellationToken] = None,
+    **kwargs,
+) -> Tuple[Iterator[Path], TraversalCounters]:
+    """Walk several roots one after another with shared statistics.
+
+    Accepts the same options as :func:`traverse_and_collect`, which are
+    applied to every root; the returned counters cover all of them.
+
+    Every root is checked before the first path is yielded, so a missing
+    root raises :class:`RootNotFoundError` on the first iteration even when
+    it follows valid ones.
+    """
+
+    counters = TraversalCounters()
+
+    def _iterator() -> Iterator[Path]:
+        for root in roots:
+            _check_root(root)
+        for root in roots:
+            if cancellation_token and cancellation_token.is_cancellation_requested():
+                break
+            iterator, _ = traverse_and_collect(
+                root,
+                excluded_folders,
+                excluded_files,
+                exclude_patterns,
+                follow_symlinks,
+                cancellation_token=cancellation_token,
+                counters=counters,
+                **kwargs,
+            )
+            yield from iterator
 
     return _iterator(), counters
@@ -1,4 +1,4 @@
-from typing import Any, Dict, List, Set, Optional, Tuple, Callable, Iterator
+from typing import Any, Dict, Iterable, List, Set, Optional, Sequence, Tuple, Callable, Iterator, Union
 from concurrent.futures import (
     ThreadPoolExecutor,
     Future,
@@ -8,21 +8,63 @@ from concurrent.futures import (
 )
 from tqdm import tqdm
 from pathlib import Path
+import codecs
 import logging
+import os
 import sys
 import io
+import threading
+from urllib.parse import quote
 
-from .traversal_core import traverse_and_collect
+from .read_ahead import ReadAheadStage
+from .traversal_core import (
+    RootNotFoundError,
+    TraversalAbortedError,
+    TraversalCounters,
+    TraversalProgress,
+    traverse_and_collect,
+    traverse_roots,
+)
+from ...cache.cache_snapshot import CacheSnapshot, find_deleted_files
+from ..archive_processor import ArchiveMember, is_archive, process_archive
 from ..file_processor import process_file
+from ..hash_service import HashService
 from ...services.event_service.cancellation import CancellationToken
+from ...services.pattern_service import CompiledFilters
 from samuraizer.utils.encoding_utils import normalize_encoding_hint
+from samuraizer.utils.file_utils.mime_detection import (
+    ClassificationRules,
+    parse_magic_overrides,
+    parse_textual_mime_types,
+)
 
 _DEFAULT_CHUNK_SIZE = 256
 _DEFAULT_PENDING_MULTIPLIER = 4
+_ON_ERROR_POLICIES = ("continue", "abort")
+# Filter lists longer than this are echoed in the summary by their length only.
+_OPTIONS_ECHO_LIST_LIMIT = 50
+# Fields that survive when an entry's content is dropped by the content budget.
+_BUDGET_KEPT_FIELDS = (
+    "size",
+    "created",
+    "modified",
+    "created_ns",
+    "modified_ns",
+    "permissions",
+    "permissions_symbolic",
+    "timezone",
+    "is_symlink",
+    "uid",
+    "gid",
+    "owner",
+    "group",
+    "file_hash",
+    "quick_hash",
+)
 
 
 def get_directory_structure(
-    root_dir: Path,
+    root_dir: Union[Path, Sequence[Path]],
     max_file_size: int,
     include_binary: bool,
     excluded_folders: Set[str],
@@ -39,6 +81,59 @@ def get_directory_structure(
     max_pending_tasks: Optional[int] = None,
     chunk_callback: Optional[Callable[[List[Dict[str, Any]]], None]] = None,
     materialize: bool = True,
+    follow_file_symlinks: bool = False,
+    ignore_files: Optional[List[str]] = None,
+    file_timeout_ms: Optional[int] = None,
+    verify_hash: bool = False,
+    binary_preview_bytes: Optional[int] = None,
+    binary_content: bool = True,
+    hash_encoding: Optional[str] = None,
+    quick_hash: bool = False,
+    group_by_parent: bool = False,
+    textual_mime_prefixes: Optional[List[str]] = None,
+    textual_mime_types: Optional[List[str]] = None,
+    dry_run: bool = False,
+    absolute_paths: bool = False,
+    filter_callback: Optional[Callable[[Dict[str, Any]], bool]] = None,
+    parse_structured: bool = False,
+    sort_by: Optional[str] = None,
+    encoding_sample_bytes: Optional[int] = None,
+    strict_decode: bool = False,
+    skip_hidden: bool = False,
+    dockerignore_path: Optional[Path] = None,
+    max_total_content_bytes: Optional[int] = None,
+    decompress: bool = False,
+    progress: Optional[TraversalProgress] = None,
+    content_first: bool = False,
+    include_mime: bool = False,
+    filters: Optional[CompiledFilters] = None,
+    strict_patterns: bool = False,
+    canonicalize_root: bool = False,
+    compress_binary: bool = False,
+    include_xattrs: bool = False,
+    magic_overrides: Optional[List[Dict[str, Any]]] = None,
+    doc_hint: bool = False,
+    encoding_by_extension: Optional[Dict[str, str]] = None,
+    on_error: str = "continue",
+    final_sort: Optional[str] = None,
+    preview_hash: bool = False,
+    expand_archives: bool = False,
+    hybrid_preview: bool = False,
+    gather_threads: Optional[int] = None,
+    cache_db_path: Optional[Union[str, Path]] = None,
+    changed_since_cache: bool = False,
+    line_count_only: bool = False,
+    enrich_callback: Optional[Callable[[Dict[str, Any]], Optional[Dict[str, Any]]]] = None,
+    url_encode_paths: bool = False,
+    stream_failures: bool = False,
+    max_failed_files: Optional[int] = None,
+    max_open_files: Optional[int] = None,
+    reencode_to: Optional[str] = None,
+    single_pass_hash: bool = False,
+    path_prefix: Optional[str] = None,
+    include_empty_dirs: bool = False,
+    include_hex_digest: bool = False,
+    io_concurrency: Optional[int] = None,
 ) -> Tuple[Dict[str, Any], Dict[str, Any]]:
 
     dir_structure: Dict[str, Any] = {} if materialize else {}
@@ -59,6 +154,59 @@ def get_directory_structure(
         cancellation_token=cancellation_token,
         chunk_size=chunk_size,
         max_pending_tasks=max_pending_tasks,
+        follow_file_symlinks=follow_file_symlinks,
+        ignore_files=ignore_files,
+        file_timeout_ms=file_timeout_ms,
+        verify_hash=verify_hash,
+        binary_preview_bytes=binary_preview_bytes,
+        binary_content=binary_content,
+        hash_encoding=hash_encoding,
+        quick_hash=quick_hash,
+        group_by_parent=group_by_parent,
+        textual_mime_prefixes=textual_mime_prefixes,
+        textual_mime_types=textual_mime_types,
+        dry_run=dry_run,
+        absolute_paths=absolute_paths,
+        filter_callback=filter_callback,
+        parse_structured=parse_structured,
+        sort_by=sort_by,
+        encoding_sample_bytes=encoding_sample_bytes,
+        strict_decode=strict_decode,
+        skip_hidden=skip_hidden,
+        dockerignore_path=dockerignore_path,
+        max_total_content_bytes=max_total_content_bytes,
+        decompress=decompress,
+        progress=progress,
+        content_first=content_first,
+        include_mime=include_mime,
+        filters=filters,
+        strict_patterns=strict_patterns,
+        canonicalize_root=canonicalize_root,
+        compress_binary=compress_binary,
+        include_xattrs=include_xattrs,
+        magic_overrides=magic_overrides,
+        doc_hint=doc_hint,
+        encoding_by_extension=encoding_by_extension,
+        on_error=on_error,
+        final_sort=final_sort,
+        preview_hash=preview_hash,
+        expand_archives=expand_archives,
+        hybrid_preview=hybrid_preview,
+        gather_threads=gather_threads,
+        cache_db_path=cache_db_path,
+        changed_since_cache=changed_since_cache,
+        line_count_only=line_count_only,
+        enrich_callback=enrich_callback,
+        url_encode_paths=url_encode_paths,
+        stream_failures=stream_failures,
+        max_failed_files=max_failed_files,
+        max_open_files=max_open_files,
+        reencode_to=reencode_to,
+        single_pass_hash=single_pass_hash,
+        path_prefix=path_prefix,
+        include_empty_dirs=include_empty_dirs,
+        include_hex_digest=include_hex_digest,
+        io_concurrency=io_concurrency,
     )
 
     summary: Dict[str, Any] = {}
@@ -78,9 +226,110 @@ def get_directory_structure(
     return dir_structure, summary
 
 
+def get_directory_summary(*args: Any, **kwargs: Any) -> Dict[str, Any]:
+    """Run a traversal and return only its summary.
+
+    Accepts the same arguments as :func:`get_directory_structure`; entries
+    are processed but discarded instead of being collected. Combine with
+    ``dry_run=True`` to skip reading file contents altogether.
+    """
+
+    kwargs["materialize"] = False
+    kwargs.pop("chunk_callback", None)
+    _, summary = get_directory_structure(*args, **kwargs)
+    return summary
+
+
+def traverse_with_callback(
+    on_entry: Callable[[Dict[str, Any]], None],
+    on_summary: Optional[Callable[[Dict[str, Any]], None]] = None,
+    **kwargs: Any,
+) -> None:
+    """Run a traversal and push every processed entry to ``on_entry``.
+
+    Accepts the same keyword arguments as :func:`get_directory_structure`
+    (except ``chunk_callback`` and ``materialize``). Both callbacks run on
+    the calling thread: ``on_entry`` once per entry in emission order and
+    ``on_summary`` once with the final summary. Unlike ``chunk_callback``,
+    an exception raised by a callback is not swallowed; it stops the
+    traversal and propagates to the caller.
+    """
+
+    kwargs.setdefault("encoding", None)
+    kwargs.setdefault("hashing_enabled", True)
+    kwargs.setdefault("progress_callback", None)
+    kwargs.setdefault("cancellation_token", None)
+    kwargs.setdefault("chunk_size", _DEFAULT_CHUNK_SIZE)
+    kwargs.setdefault("max_pending_tasks", None)
+
+    chunks = generate_directory_chunks(**kwargs)
+    try:
+        for payload in chunks:
+            if "entries" in payload:
+                for entry in payload["entries"]:
+                    on_entry(entry)
+            elif "summary" in payload and on_summary is not None:
+                on_summary(payload["summary"])
+    finally:
+        # Shuts the worker pool down when a callback raised mid-traversal.
+        chunks.close()
+
+
+def gather_paths(
+    root_dir: Path,
+    excluded_folders: Set[str],
+    excluded_files: Set[str],
+    exclude_patterns: List[str],
+    follow_symlinks: bool,
+    cancellation_token: Optional[CancellationToken] = None,
+    follow_file_symlinks: bool = False,
+    ignore_files: Optional[List[str]] = None,
+    skip_hidden: bool = False,
+    dockerignore_path: Optional[Path] = None,
+    filters: Optional[CompiledFilters] = None,
+    strict_patterns: bool = False,
+    path_prefix: Optional[str] = None,
+) -> Dict[str, Any]:
+    """List the files a traversal would process without reading any of them.
+
+    The exclusion rules are exactly those of :func:`get_directory_structure`,
+    so the returned paths match the files a full run would include.
+
+    Returns:
+        Dict[str, Any]: ``files`` (paths as strings), ``included`` and
+        ``excluded`` counts, and ``cancelled``.
+
+    Raises:
+        RootNotFoundError: If ``root_dir`` does not exist or is not a directory.
+    """
+
+    file_iterator, counters = traverse_and_collect(
+        Path(root_dir),
+        excluded_folders,
+        excluded_files,
+        exclude_patterns,
+        follow_symlinks,
+        cancellation_token=cancellation_token,
+        follow_file_symlinks=follow_file_symlinks,
+        ignore_files=ignore_files,
+        skip_hidden=skip_hidden,
+        dockerignore_path=dockerignore_path,
+        filters=filters,
+        strict_patterns=strict_patterns,
+        path_prefix=path_prefix,
+    )
+    files = [str(file_path) for file_path in file_iterator]
+    return {
+        "files": files,
+        "included": counters.included,
+        "excluded": counters.excluded,
+        "cancelled": bool(cancellation_token and cancellation_token.is_cancellation_requested()),
+    }
+
+
 def generate_directory_chunks(
     *,
-    root_dir: Path,
+    root_dir: Union[Path, Sequence[Path]],
     max_file_size: int,
     include_binary: bool,
     excluded_folders: Set[str],
@@ -95,21 +344,315 @@ def generate_directory_chunks(
     cancellation_token: Optional[CancellationToken],
     chunk_size: int,
     max_pending_tasks: Optional[int],
+    follow_file_symlinks: bool = False,
+    ignore_files: Optional[List[str]] = None,
+    file_timeout_ms: Optional[int] = None,
+    verify_hash: bool = False,
+    binary_preview_bytes: Optional[int] = None,
+    binary_content: bool = True,
+    hash_encoding: Optional[str] = None,
+    quick_hash: bool = False,
+    group_by_parent: bool = False,
+    textual_mime_prefixes: Optional[List[str]] = None,
+    textual_mime_types: Optional[List[str]] = None,
+    dry_run: bool = False,
+    absolute_paths: bool = False,
+    filter_callback: Optional[Callable[[Dict[str, Any]], bool]] = None,
+    parse_structured: bool = False,
+    sort_by: Optional[str] = None,
+    encoding_sample_bytes: Optional[int] = None,
+    strict_decode: bool = False,
+    skip_hidden: bool = False,
+    dockerignore_path: Optional[Path] = None,
+    max_total_content_bytes: Optional[int] = None,
+    decompress: bool = False,
+    progress: Optional[TraversalProgress] = None,
+    content_first: bool = False,
+    include_mime: bool = False,
+    filters: Optional[CompiledFilters] = None,
+    strict_patterns: bool = False,
+    canonicalize_root: bool = False,
+    compress_binary: bool = False,
+    include_xattrs: bool = False,
+    magic_overrides: Optional[List[Dict[str, Any]]] = None,
+    doc_hint: bool = False,
+    encoding_by_extension: Optional[Dict[str, str]] = None,
+    on_error: str = "continue",
+    final_sort: Optional[str] = None,
+    preview_hash: bool = False,
+    expand_archives: bool = False,
+    hybrid_preview: bool = False,
+    gather_threads: Optional[int] = None,
+    cache_db_path: Optional[Union[str, Path]] = None,
+    changed_since_cache: bool = False,
+    line_count_only: bool = False,
+    enrich_callback: Optional[Callable[[Dict[str, Any]], Optional[Dict[str, Any]]]] = None,
+    url_encode_paths: bool = False,
+    stream_failures: bool = False,
+    max_failed_files: Optional[int] = None,
+    max_open_files: Optional[int] = None,
+    reencode_to: Optional[str] = None,
+    single_pass_hash: bool = False,
+    path_prefix: Optional[str] = None,
+    include_empty_dirs: bool = False,
+    include_hex_digest: bool = False,
+    io_concurrency: Optional[int] = None,
 ) -> Iterator[Dict[str, Any]]:
+    """Process files progressively, yielding ``{"entries": [...]}`` payloads
+    followed by a final ``{"summary": {...}}``.
+
+    The summary always carries the same keys, with zeros and empty lists or
+    dicts when there is nothing to report; only ``hash_algorithm`` and
+    ``manifest_hash`` are left out when hashing is disabled. Keys that belong
+    to an option that is off are null: ``deleted_files`` without
+    ``cache_db_path``, ``lines_by_extension`` without ``line_count_only``, and
+    ``unchanged_files`` and ``deleted`` without ``changed_since_cache``.
+
+    The summary's ``options`` echoes the parameters that produced the result:
+    the canonical roots, the effective number of worker threads,
+    ``max_file_size``, the hash algorithm (None without hashing) and the
+    exclusion lists actually applied. Lists longer than 50 items are
+    reported as ``{"count": n}``.
+
+    ``max_pending_tasks`` bounds how many files are queued in the worker
+    pool at once (by default four per worker, but at least ``chunk_size``
+    and ``io_concurrency``).
+    A deeper queue keeps workers busy when results are consumed in bursts,
+    while a shallower one caps the number of previews held in memory at the
+    cost of idle workers whenever the consumer falls behind. It must be at
+    least 1.
+
+    ``io_concurrency`` adds a read-ahead stage in front of the worker pool:
+    up to that many files are read concurrently on separate threads, pulling
+    them into the page cache before a worker hashes and decodes them (see
+    :class:`ReadAheadStage`). On high-latency storage such as network mounts
+    this overlaps read latency without adding processing threads.
+
+    ``filter_callback`` receives every entry after processing; entries for
+    which it returns a falsy value are dropped and counted as ``filter`` in
+    the exclusion breakdown. The callback runs on the consuming thread, one
+    entry at a time, so an expensive callback throttles the whole pipeline.
+
+    ``url_encode_paths`` adds ``url_path``, the relative path percent-encoded
+    for use in URLs (slashes are kept, reserved and non-ASCII characters are
+    escaped as UTF-8); the other path fields are unchanged.
+
+    ``max_open_files`` caps how many files are processed (and so held open)
+    at the same time, independently of ``threads``, to stay below a low
+    descriptor limit (``ulimit -n``). Time spent waiting for a slot does not
+    count towards ``file_timeout_ms``; a file that timed out gives its slot
+    back although its blocked read may still hold a descriptor.
+
+    ``file_timeout_ms`` bounds how long a worker waits for a single file.
+    Each file then runs on its own daemon thread, and one that takes longer
+    is reported as ``{"type": "error", "reason": "timeout"}`` while the
+    worker moves on; the stuck thread neither blocks the pool nor the exit
+    of the process.
+
+    ``stream_failures`` reports every failure counted in ``failed_count`` as
+    a ``{"failure": {"file": ..., "error": ...}}`` payload as soon as it fails,
+    ahead of the entries emitted at the same time. ``max_failed_files`` caps
+    the summary's ``failed_files`` list so pathological trees cannot grow it
+    without bound; ``failed_count`` always holds the full number and
+    ``failed_files_truncated`` tells whether the list was cut.
+
+    ``enrich_callback`` is called with every entry that passed the filter,
+    also on the consuming thread, and the keys of the dict it returns (if
+    any) are merged into the entry, e.g. a project ID derived from the path.
+    Enrichment is therefore serialized, however many workers process files.
+    An exception from the callback turns the entry's info into an error
+    record with the reason ``"enrich_callback"`` and lists the file in
+    ``failed_files``.
+
+    ``sort_by`` (``name``, ``size``, ``mtime`` or ``path``) processes files in
+    a deterministic order. The whole file list is gathered before processing
+    starts, and size/mtime sorts stat every file up front; without it files
+    stream in filesystem order.
+
+    ``max_total_content_bytes`` caps the cumulative size of ``content``
+    fields. Once it has been exceeded, later entries keep their metadata but
+    their info is replaced with ``{"type": "excluded", "reason":
+    "content_budget"}`` and the summary reports ``content_budget_exhausted``.
+
+    ``filters`` is a prebuilt :class:`CompiledFilters` that replaces
+    ``excluded_folders``, ``excluded_files`` and ``exclude_patterns``, so
+    repeated traversals with the same rules compile them only once.
+    ``strict_patterns`` raises ``ValueError`` for invalid exclusion patterns
+    instead of logging and skipping them.
+
+    ``root_dir`` may also be a list of roots. They are walked one after
+    another into a single result with one summary, and each entry's parent is
+    prefixed with the name of its root (or the root's index when names are
+    missing or ambiguous).
+
+    ``canonicalize_root`` resolves every root to its real path (following
+    symlinks and ``..``) before gathering, so relative paths do not depend on
+    how the root was spelled, and drops roots that resolve to the same
+    directory.
+
+    A root that does not exist or is not a directory raises
+    :class:`RootNotFoundError` (a ``FileNotFoundError``) on the first
+    iteration, before any file of any root is processed.
+
+    ``progress`` is updated as files are processed and receives the final
+    number of included files once gathering has finished, so other threads
+    can estimate the remaining work. Its ``current`` field names the file
+    that was processed last for "now scanning" displays; being polled rather
+    than pushed, it never floods consumers however fast files complete.
+
+    ``encoding_by_extension`` maps file extensions (``".sjis"`` or ``"sjis"``)
+    to the encoding used for matching files instead of detection; such files
+    are always read as text. Other files keep using ``encoding``. Mapping an
+    extension to ``"auto"`` restores detection for it.
+
+    ``final_sort`` sorts the processed entries before they are emitted, by
+    ``name``, ``path``, ``size``, ``modified``, ``content_length`` or
+    ``hash`` (prefix with ``-`` for descending order). Unlike ``sort_by`` it
+    can use values computed during processing, but every entry is held in
+    memory until the traversal has finished, so nothing is streamed before
+    then. It cannot be combined with ``group_by_parent``, and ``hash``
+    (which orders by the hex digest, whatever ``hash_encoding`` is) requires
+    ``hashing_enabled``.
+
+    ``expand_archives`` treats zip and tar archives (see
+    :data:`ARCHIVE_SUFFIXES`) like directories: instead of an entry for the
+    archive, every file inside it is emitted with a virtual parent such as
+    ``dist/bundle.zip/docs``, classified and previewed from its decompressed
+    bytes. Nested archives are not expanded, and archives that cannot be
+    read are processed as regular files.
+
+    An unexpected exception while processing a file is contained to that
+    file: it becomes an error entry with the reason ``"panic"`` and is listed
+    in the summary's ``failed_files``.
+
+    ``hybrid_preview`` cuts text previews at the first binary byte and
+    reports the offset of that tail as ``binary_tail_offset`` (see
+    :func:`process_file`).
+
+    ``gather_threads`` sets how many threads list directories ahead of the
+    walk (defaulting to ``threads``), so I/O-bound enumeration can use more
+    or fewer threads than CPU-bound processing. Gathering order is unchanged.
+
+    ``reencode_to`` adds every text preview re-encoded in that encoding as
+    ``content_bytes_b64`` (see :func:`process_file`); an unknown encoding
+    raises ``ValueError``.
+
+    ``path_prefix`` limits the walk to a subtree of the root (e.g.
+    ``"src/app"``) without changing how paths are reported, so the entries
+    match those of a full scan; sibling directories are never entered (see
+    :func:`traverse_and_collect`).
+
+    ``include_empty_dirs`` adds an entry ``{"type": "directory", "is_empty":
+    true}`` for every directory that holds neither files nor subdirectories
+    once the exclusions apply, e.g. one containing only excluded files, so
+    such directories are not lost. They are emitted after all files.
+
+    ``single_pass_hash`` hashes files for the cache from the same reads that
+    produce their previews instead of opening them a second time (see
+    :func:`process_file`).
+
+    ``include_hex_digest`` keeps each hashed file's content digest in hex as
+    ``hex_digest`` in its info, whatever ``hash_encoding`` is, for outputs
+    with a fixed hash column such as Arrow. It needs ``hashing_enabled``.
+
+    ``line_count_only`` counts the lines of text files instead of previewing
+    them and reports binary files by their metadata only (see
+    :func:`process_file`). The summary breaks the counts down by extension as
+    ``lines_by_extension``, mapping e.g. ``".py"`` to ``{"files": n,
+    "lines": m}``.
+
+    ``cache_db_path`` points at a cache database to reconcile the tree
+    against: cached files below the roots that no longer exist are listed in
+    the summary as ``deleted_files``.
+
+    ``changed_since_cache`` turns the traversal into a change detector
+    against the cache database at ``cache_db_path``, whose rows are read
+    before processing starts. Every file is still processed, but only those
+    that are new (``"changed": "added"``) or differ from their cached row by
+    hash, or by size and mtime when no hash is available (``"changed":
+    "modified"``), are emitted. The summary counts the rest as
+    ``unchanged_files`` and lists cached files below the roots that no
+    longer exist as ``deleted``.
+
+    ``on_error`` decides what happens when a file yields an error entry:
+    ``"continue"`` (the default) keeps going, while ``"abort"`` stops
+    scheduling, emits the entries collected so far (including the failed one)
+    and raises :class:`TraversalAbortedError`.
+
+    ``magic_overrides`` is a list of ``{"hex_prefix": ..., "is_binary": ...}``
+    rules that classify files by their leading bytes before any other
+    heuristic (see :func:`parse_magic_overrides`); the first matching
+    rule wins. The rules apply to this traversal only.
+
+    ``textual_mime_prefixes`` and ``textual_mime_types`` add MIME prefixes
+    and exact MIME types whose libmagic results count as text, likewise for
+    this traversal only.
+    """
+    if final_sort is not None:
+        sort_final_entries = _final_sorter(final_sort)
+        if group_by_parent:
+            raise ValueError("final_sort cannot be combined with group_by_parent")
+        if final_sort.lstrip("-") == "hash" and not hashing_enabled:
+            raise ValueError("final_sort by hash requires hashing_enabled")
+    if changed_since_cache and cache_db_path is None:
+        raise ValueError("changed_since_cache requires cache_db_path")
+    if max_pending_tasks is not None and max_pending_tasks < 1:
+        raise ValueError("max_pending_tasks must be at least 1")
+    if io_concurrency is not None and io_concurrency < 1:
+        raise ValueError("io_concurrency must be at least 1")
+    if reencode_to is not None:
+        try:
+            codecs.lookup(reencode_to)
+        except LookupError as e:
+            raise ValueError(f"Unknown reencode_to encoding '{reencode_to}'") from e
+    if on_error not in _ON_ERROR_POLICIES:
+        raise ValueError(
+            f"Unsupported on_error policy '{on_error}'; expected one of {', '.join(_ON_ERROR_POLICIES)}"
+        )
     chunk_size = max(1, chunk_size)
     max_workers = max(1, threads)
-    pending_limit = max_pending_tasks or max(max_workers * _DEFAULT_PENDING_MULTIPLIER, chunk_size)
+    pending_limit = max_pending_tasks or max(
+        max_workers * _DEFAULT_PENDING_MULTIPLIER, chunk_size, io_concurrency or 0
+    )
     normalized_encoding = normalize_encoding_hint(encoding)
+    extension_encodings = {
+        _normalize_extension(extension): normalize_encoding_hint(label)
+        for extension, label in (encoding_by_extension or {}).items()
+    }
+    textual_prefixes, textual_types = parse_textual_mime_types(
+        textual_mime_prefixes or (), textual_mime_types or ()
+    )
+    classification_rules = ClassificationRules(
+        magic_overrides=parse_magic_overrides(magic_overrides or ()),
+        textual_prefixes=textual_prefixes,
+        textual_types=textual_types,
+    )
 
-    file_iterator, counters = traverse_and_collect(
-        root_dir,
+    roots = _RootSet(root_dir, canonicalize=canonicalize_root)
+    file_iterator, counters = traverse_roots(
+        roots.paths,
         excluded_folders,
         excluded_files,
         exclude_patterns,
         follow_symlinks,
         cancellation_token=cancellation_token,
+        follow_file_symlinks=follow_file_symlinks,
+        ignore_files=ignore_files,
+        skip_hidden=skip_hidden,
+        dockerignore_path=dockerignore_path,
+        filters=filters,
+        strict_patterns=strict_patterns,
+        gather_threads=max(1, gather_threads or threads),
+        path_prefix=path_prefix,
     )
 
+    if dry_run:
+        yield {"summary": _dry_run_summary(file_iterator, counters, cancellation_token)}
+        return
+
+    if sort_by is not None:
+        file_iterator = iter(_sort_files(roots, file_iterator, sort_by, group_by_parent))
+
     logging.debug("Starting progressive processing pipeline")
 
     # Create a fallback file object if sys.stdout is None or not available
@@ -124,8 +667,79 @@ def generate_directory_chunks(
     )
 
     failed_files: List[Dict[str, str]] = []
+    failed_count = 0
+    # Failures not yet emitted as ``{"failure": ...}`` payloads.
+    streamed_failures: List[Dict[str, str]] = []
     processed_count = 0
     chunk: List[Dict[str, Any]] = []
+    timeout_seconds = file_timeout_ms / 1000.0 if file_timeout_ms and file_timeout_ms > 0 else None
+    processing_exclusions: Dict[str, int] = {}
+    # Workers always report hex digests when hashing is enabled so that the
+    # manifest hash does not depend on the requested output encoding.
+    worker_hash_encoding = "hex" if hashing_enabled else hash_encoding
+    manifest_entries: List[Tuple[str, str]] = []
+    # With ``group_by_parent`` entries are buffered per directory.  Files of a
+    # directory are gathered consecutively, so a directory is complete once
+    # gathering has moved on and none of its files are still pending.
+    groups: Dict[str, List[Dict[str, Any]]] = {}
+    group_pending: Dict[str, int] = {}
+    gathering_parent: Optional[str] = None
+    content_bytes = 0
+    content_budget_exhausted = False
+    first_error: Optional[Tuple[Path, Dict[str, Any]]] = None
+    # With ``final_sort`` every entry is held back until processing has finished.
+    final_entries: List[Tuple[Dict[str, Any], Optional[str]]] = []
+    # Gathering-order index of every pending file, kept only for ``progress``.
+    file_indices: Dict[Path, int] = {}
+    scheduled_count = 0
+    # Cache rows from before this run, kept only for ``changed_since_cache``,
+    # and the resolved paths of the files seen so far when a cache is given.
+    cache_snapshot = CacheSnapshot.load(cache_db_path) if changed_since_cache else None
+    seen_paths: Set[str] = set()
+    unchanged_files = 0
+    lines_by_extension: Dict[str, Dict[str, int]] = {}
+    # Workers take a slot before touching a file; every open of a file happens
+    # while its slot is held, so this bounds the descriptors in use.
+    open_files = threading.BoundedSemaphore(max(1, max_open_files)) if max_open_files else None
+
+    def record_failure(file_path: Path, error: str) -> None:
+        nonlocal failed_count
+        failure = {"file": str(file_path), "error": error}
+        failed_count += 1
+        if max_failed_files is None or len(failed_files) < max_failed_files:
+            failed_files.append(failure)
+        if stream_failures:
+            streamed_failures.append(failure)
+
+    def emit_failures() -> Iterator[Dict[str, Any]]:
+        pending_failures = streamed_failures[:]
+        streamed_failures.clear()
+        for failure in pending_failures:
+            yield {"failure": failure}
+
+    def apply_content_budget(file_info: Dict[str, Any]) -> Dict[str, Any]:
+        nonlocal content_bytes, content_budget_exhausted
+        if max_total_content_bytes is None:
+            return file_info
+        if content_budget_exhausted:
+            if "content" not in file_info:
+                return file_info
+            processing_exclusions["content_budget"] = (
+                processing_exclusions.get("content_budget", 0) + 1
+            )
+            budgeted: Dict[str, Any] = {"type": "excluded", "reason": "content_budget"}
+            budgeted.update(
+                {key: file_info[key] for key in _BUDGET_KEPT_FIELDS if key in file_info}
+            )
+            return budgeted
+        content_bytes += _content_size(file_info.get("content"))
+        if content_bytes > max_total_content_bytes:
+            logging.warning(
+                "Content budget of %d bytes exhausted; omitting content of remaining files",
+                max_total_content_bytes,
+            )
+            content_budget_exhausted = True
+        return file_info
 
     def emit_chunk(force: bool = False) -> Iterator[Dict[str, Any]]:
         nonlocal chunk
@@ -134,11 +748,179 @@ def generate_directory_chunks(
             chunk = []
             yield {"entries": to_emit}
 
+    def emit_groups(force: bool = False) -> Iterator[Dict[str, Any]]:
+        for parent in list(groups):
+            if force or (parent != gathering_parent and not group_pending.get(parent)):
+                group_pending.pop(parent, None)
+                yield {"parent": parent, "entries": groups.pop(parent)}
+
+    def record_entry(
+        file_path: Path,
+        parent_str: str,
+        filename: str,
+        file_info: Optional[Dict[str, Any]],
+        member_path: Optional[str] = None,
+    ) -> None:
+        nonlocal first_error, unchanged_files
+        if first_error is None and file_info is not None and file_info.get("type") == "error":
+            first_error = (file_path, file_info)
+        if file_info is not None and file_info.get("type") == "excluded":
+            reason = str(file_info.get("reason", "unknown"))
+            processing_exclusions[reason] = processing_exclusions.get(reason, 0) + 1
+        if hashing_enabled and isinstance(file_info, dict) and "file_hash" in file_info:
+            hex_digest = file_info.pop("file_hash")
+            if hash_encoding is not None:
+                file_info["file_hash"] = HashService.encode_digest(hex_digest, hash_encoding)
+                for extra_hash in ("quick_hash", "preview_hash"):
+                    if file_info.get(extra_hash):
+                        file_info[extra_hash] = HashService.encode_digest(
+                            file_info[extra_hash], hash_encoding
+                        )
+            if include_hex_digest:
+                file_info["hex_digest"] = hex_digest
+        else:
+            hex_digest = None
+        if file_info is not None:
+            entry = {
+                "parent": parent_str,
+                "filename": filename,
+                "relative_path": _compose_relative_path(parent_str, filename),
+                "info": file_info,
+            }
+            if absolute_paths:
+                entry["absolute_path"] = _absolute_path(file_path)
+                if member_path is not None:
+                    entry["absolute_path"] += f"/{member_path}"
+            if url_encode_paths:
+                entry["url_path"] = quote(entry["relative_path"], safe="/")
+            if filter_callback is not None and not _passes_filter(filter_callback, entry):
+                processing_exclusions["filter"] = processing_exclusions.get("filter", 0) + 1
+            else:
+                if enrich_callback is not None and not _enrich_entry(enrich_callback, entry):
+                    record_failure(file_path, entry["info"]["exception_message"])
+                    file_info = entry["info"]
+                if isinstance(file_info, dict):
+                    entry["info"] = apply_content_budget(file_info)
+                if hex_digest is not None:
+                    manifest_entries.append((entry["relative_path"], hex_digest))
+                if line_count_only and isinstance(file_info, dict) and "line_count" in file_info:
+                    stats = lines_by_extension.setdefault(
+                        Path(filename).suffix.lower(), {"files": 0, "lines": 0}
+                    )
+                    stats["files"] += 1
+                    stats["lines"] += file_info["line_count"]
+                if cache_snapshot is not None:
+                    # Archive members are judged by the archive's own cache row.
+                    change = cache_snapshot.change(
+                        file_path, hex_digest if member_path is None else None
+                    )
+                    if change is None:
+                        unchanged_files += 1
+                        return
+                    entry["changed"] = change
+                add_entry(parent_str, entry, hex_digest)
+
+    def add_entry(parent_str: str, entry: Dict[str, Any], hex_digest: Optional[str] = None) -> None:
+        if group_by_parent:
+            groups.setdefault(parent_str, []).append(entry)
+        elif final_sort is not None:
+            final_entries.append((entry, hex_digest))
+        else:
+            chunk.append(entry)
+
+    def record_empty_dir(directory: Path) -> None:
+        parent_str = roots.parent(directory)
+        entry: Dict[str, Any] = {
+            "parent": parent_str,
+            "filename": directory.name,
+            "relative_path": _compose_relative_path(parent_str, directory.name),
+            "info": {"type": "directory", "is_empty": True},
+        }
+        if absolute_paths:
+            entry["absolute_path"] = _absolute_path(directory)
+        if url_encode_paths:
+            entry["url_path"] = quote(entry["relative_path"], safe="/")
+        if filter_callback is not None and not _passes_filter(filter_callback, entry):
+            processing_exclusions["filter"] = processing_exclusions.get("filter", 0) + 1
+        else:
+            add_entry(parent_str, entry)
+
+    def finish_file(file_path: Path, parent_str: str, filename: str) -> None:
+        nonlocal processed_count
+        if group_by_parent:
+            group_pending[parent_str] = group_pending.get(parent_str, 1) - 1
+
+        processed_count += 1
+        if cache_db_path is not None:
+            seen_paths.add(str(file_path.resolve()))
+        if progress is not None:
+            progress.processed = processed_count
+            progress.current = (
+                file_indices.pop(file_path, processed_count - 1),
+                _compose_relative_path(parent_str, filename),
+            )
+        pbar.update(1)
+        if progress_callback:
+            try:
+                progress_callback(processed_count)
+            except Exception:
+                logging.exception("Progress callback failed")
+
+    def record_result(file_path: Path, filename: str, file_info: Optional[Dict[str, Any]]) -> None:
+        parent_str = roots.parent(file_path)
+        record_entry(file_path, parent_str, filename, file_info)
+        finish_file(file_path, parent_str, filename)
+
+    def record_archive(file_path: Path, members: List[ArchiveMember]) -> None:
+        # The archive stands in for a directory holding its members.
+        parent_str = roots.parent(file_path)
+        archive_path = _compose_relative_path(parent_str, file_path.name)
+        for member_path, member_info in members:
+            member_parent, _, member_name = member_path.rpartition("/")
+            record_entry(
+                file_path,
+                f"{archive_path}/{member_parent}" if member_parent else archive_path,
+                member_name,
+                member_info,
+                member_path,
+            )
+        finish_file(file_path, parent_str, file_path.name)
+
+    def _process_task(file_path: Path, *args: Any, **kwargs: Any) -> Any:
+        if open_files is None:
+            return _process_timed(file_path, *args, **kwargs)
+        with open_files:
+            return _process_timed(file_path, *args, **kwargs)
+
+    def _process_timed(file_path: Path, *args: Any, **kwargs: Any) -> Any:
+        # The deadline starts once the file holds its slot, so time spent
+        # queued does not count towards it.
+        if timeout_seconds is None:
+            return _process_open_file(file_path, *args, **kwargs)
+        return _run_with_deadline(timeout_seconds, _process_open_file, file_path, *args, **kwargs)
+
+    def _process_open_file(file_path: Path, *args: Any, **kwargs: Any) -> Any:
+        if expand_archives and is_archive(file_path) and file_path.is_file():
+            members = process_archive(
+                file_path,
+                max_file_size,
+                include_binary,
+                encoding=kwargs.get("encoding"),
+                hashing_enabled=hashing_enabled,
+                hash_encoding=worker_hash_encoding,
+                binary_preview_bytes=binary_preview_bytes,
+                classification_rules=classification_rules,
+            )
+            if members is not None:
+                return members
+        return process_file(file_path, *args, **kwargs)
+
     pending: Dict[Future[Tuple[str, Any]], Path] = {}
     scheduling_finished = False
 
     def _schedule_more(executor: ThreadPoolExecutor) -> None:
-        nonlocal scheduling_finished
+        nonlocal scheduling_finished, gathering_parent, scheduled_count
+        submit = read_stage.submit if read_stage is not None else executor.submit
         while not scheduling_finished and len(pending) < pending_limit:
             if cancellation_token and cancellation_token.is_cancellation_requested():
                 scheduling_finished = True
@@ -147,94 +929,163 @@ def generate_directory_chunks(
                 file_path = next(file_iterator)
             except StopIteration:
                 scheduling_finished = True
+                gathering_parent = None
+                if progress is not None:
+                    progress.included = counters.included
                 break
 
-            future = executor.submit(
-                process_file,
+            if group_by_parent:
+                gathering_parent = roots.parent(file_path)
+                group_pending[gathering_parent] = group_pending.get(gathering_parent, 0) + 1
+
+            future = submit(
+                _process_task,
                 file_path,
                 max_file_size,
                 include_binary,
                 image_extensions,
-                encoding=normalized_encoding,
+                encoding=extension_encodings.get(file_path.suffix.lower(), normalized_encoding),
                 hashing_enabled=hashing_enabled,
+                verify_hash=verify_hash,
+                binary_preview_bytes=binary_preview_bytes,
+                binary_content=binary_content,
+                hash_encoding=worker_hash_encoding,
+                quick_hash=quick_hash,
+                parse_structured=parse_structured,
+                encoding_sample_bytes=encoding_sample_bytes,
+                strict_decode=strict_decode,
+                decompress=decompress,
+                content_first=content_first,
+                include_mime=include_mime,
+                compress_binary=compress_binary,
+                include_xattrs=include_xattrs,
+                doc_hint=doc_hint,
+                assume_text=extension_encodings.get(file_path.suffix.lower()) is not None,
+                preview_hash=preview_hash,
+                hybrid_preview=hybrid_preview,
+                line_count_only=line_count_only,
+                reencode_to=reencode_to,
+                single_pass_hash=single_pass_hash,
+                classification_rules=classification_rules,
             )
             pending[future] = file_path
+            if progress is not None:
+                file_indices[file_path] = scheduled_count
+            scheduled_count += 1
 
             # Update the progress bar total dynamically as we discover files
             if pbar.total != counters.included:
                 pbar.total = counters.included
                 pbar.refresh()
 
+    aborted: Optional[TraversalAbortedError] = None
+    executor = ThreadPoolExecutor(max_workers=max_workers)
+    read_stage = (
+        ReadAheadStage(io_concurrency, max_file_size, executor, open_files)
+        if io_concurrency
+        else None
+    )
     try:
-        with ThreadPoolExecutor(max_workers=max_workers) as executor:
-            _schedule_more(executor)
-
-            while pending:
-                if cancellation_token and cancellation_token.is_cancellation_requested():
-                    logging.info("Cancellation requested; draining pending results")
-                    for future in list(pending.keys()):
-                        if not future.done():
-                            future.cancel()
-
-                done, _ = wait(pending.keys(), return_when=FIRST_COMPLETED)
-                for future in done:
-                    file_path = pending.pop(future)
-                    if future.cancelled():
-                        continue
-
-                    try:
-                        filename, file_info = future.result()
-                    except CancelledError:
-                        continue
-                    except Exception as exc:  # pragma: no cover - safety net
-                        logging.error(f"Error when processing the file {file_path}: {exc}")
-                        file_info = {
-                            "type": "error",
-                            "content": f"Errors during processing: {str(exc)}",
-                            "exception_type": type(exc).__name__,
-                            "exception_message": str(exc),
-                        }
-                        failed_files.append({"file": str(file_path), "error": str(exc)})
-                        filename = file_path.name
-
-                    parent_str = _normalize_parent(root_dir, file_path)
-                    if file_info is not None:
-                        entry = {
-                            "parent": parent_str,
-                            "filename": filename,
-                            "info": file_info,
-                        }
-                        chunk.append(entry)
-
-                    processed_count += 1
-                    pbar.update(1)
-                    if progress_callback:
-                        try:
-                            progress_callback(processed_count)
-                        except Exception:
-                            logging.exception("Progress callback failed")
-
-                    for emitted in emit_chunk():
-                        yield emitted
-
-                if not scheduling_finished:
-                    _schedule_more(executor)
+        _schedule_more(executor)
+
+        while pending:
+            if cancellation_token and cancellation_token.is_cancellation_requested():
+                logging.info("Cancellation requested; draining pending results")
+                for future in list(pending.keys()):
+                    if not future.done():
+                        future.cancel()
+
+            done, _ = wait(pending.keys(), return_when=FIRST_COMPLETED)
+            for future in done:
+                file_path = pending.pop(future)
+                if future.cancelled():
+                    continue
+
+                try:
+                    result = future.result()
+                except CancelledError:
+                    continue
+                except _FileTimeout:
+                    message = f"Processing timed out after {file_timeout_ms} ms"
+                    logging.error(f"Error when processing the file {file_path}: {message}")
+                    record_failure(file_path, message)
+                    result = (
+                        file_path.name,
+                        {"type": "error", "reason": "timeout", "content": message},
+                    )
+                except Exception as exc:
+                    # Processing handles expected failures itself, so this is
+                    # a bug; it must not take the rest of the traversal down.
+                    logging.exception(f"Unexpected error when processing the file {file_path}")
+                    file_info = {
+                        "type": "error",
+                        "reason": "panic",
+                        "content": f"Errors during processing: {str(exc)}",
+                        "exception_type": type(exc).__name__,
+                        "exception_message": str(exc),
+                    }
+                    record_failure(file_path, str(exc))
+                    result = (file_path.name, file_info)
+
+                if isinstance(result, list):
+                    record_archive(file_path, result)
+                else:
+                    record_result(file_path, *result)
+
+            if on_error == "abort" and first_error is not None:
+                logging.error("Aborting traversal after the first failed file: %s", first_error[0])
+                aborted = TraversalAbortedError(*first_error)
+                break
+            for emitted in emit_failures():
+                yield emitted
+            for emitted in emit_chunk():
+                yield emitted
+            for emitted in emit_groups():
+                yield emitted
+
+            if not scheduling_finished:
+                _schedule_more(executor)
+
+        if aborted is None and include_empty_dirs and not (
+            cancellation_token and cancellation_token.is_cancellation_requested()
+        ):
+            for directory in counters.empty_dirs:
+                record_empty_dir(directory)
 
     except KeyboardInterrupt:  # pragma: no cover - interactive safeguard
         logging.warning("\nCancellation by user. Attempts to terminate running tasks...")
         raise
     finally:
+        if read_stage is not None:
+            read_stage.shutdown()
+        executor.shutdown(wait=True, cancel_futures=True)
         if pbar.total != counters.included:
             pbar.total = counters.included
             pbar.refresh()
-        for emitted in emit_chunk(force=True):
-            yield emitted
         pbar.close()
 
+    # Buffered entries are flushed on the normal path only: yielding from the
+    # ``finally`` block would turn a consumer's ``close()`` into a RuntimeError.
+    if final_sort is not None:
+        sorted_entries = sort_final_entries(final_entries)
+        for start in range(0, len(sorted_entries), chunk_size):
+            yield {"entries": sorted_entries[start : start + chunk_size]}
+    for emitted in emit_failures():
+        yield emitted
+    for emitted in emit_chunk(force=True):
+        yield emitted
+    for emitted in emit_groups(force=True):
+        yield emitted
+    if aborted is not None:
+        raise aborted
+
     included_files = counters.included
     excluded_files_count = counters.excluded
     total_files = included_files + excluded_files_count
     excluded_percentage = (excluded_files_count / total_files * 100) if total_files else 0.0
+    excluded_breakdown: Dict[str, int] = dict(counters.excluded_reasons)
+    for reason, count in processing_exclusions.items():
+        excluded_breakdown[reason] = excluded_breakdown.get(reason, 0) + count
 
     if cancellation_token and cancellation_token.is_cancellation_requested():
         logging.info(
@@ -245,21 +1096,61 @@ def generate_directory_chunks(
     summary: Dict[str, Any] = {
         "total_files": total_files,
         "excluded_files": excluded_files_count,
+        "excluded_by_name": counters.excluded_by_name,
+        "excluded_by_pattern": counters.excluded_by_pattern,
         "included_files": included_files,
         "excluded_percentage": excluded_percentage,
         "failed_files": failed_files,
+        "failed_count": failed_count,
+        "failed_files_truncated": failed_count > len(failed_files),
         "stopped_early": bool(cancellation_token and cancellation_token.is_cancellation_requested()),
         "processed_files": processed_count,
+        "escaped_symlinks": counters.escaped_symlinks,
+        "walk_errors": counters.walk_errors,
+        "excluded_breakdown": excluded_breakdown,
+        "content_budget_exhausted": content_budget_exhausted,
+        "options": _options_echo(
+            roots.paths,
+            max_workers,
+            max_file_size,
+            hashing_enabled,
+            filters.excluded_folders if filters else excluded_folders,
+            filters.excluded_files if filters else excluded_files,
+            filters.exclude_patterns if filters else exclude_patterns,
+        ),
     }
+    summary["lines_by_extension"] = (
+        dict(sorted(lines_by_extension.items())) if line_count_only else None
+    )
+    summary["unchanged_files"] = unchanged_files if cache_snapshot is not None else None
+    summary["deleted"] = (
+        cache_snapshot.deleted(seen_paths, roots.paths) if cache_snapshot is not None else None
+    )
+    if cache_db_path is None:
+        summary["deleted_files"] = None
+    elif summary["deleted"] is not None:
+        summary["deleted_files"] = summary["deleted"]
+    else:
+        summary["deleted_files"] = find_deleted_files(cache_db_path, seen_paths, roots.paths)
 
     if hashing_enabled:
         summary["hash_algorithm"] = "xxhash"
+        summary["manifest_hash"] = HashService.compute_manifest_hash(manifest_entries)
 
     logging.info("Analysis Summary:")
     logging.info("  Processed files: %d", included_files)
     logging.info("  Excluded files: %d (%.2f%%)", excluded_files_count, excluded_percentage)
-    if failed_files:
-        logging.info("  Failed files: %d", len(failed_files))
+    logging.info(
+        "    by name: %d, by pattern: %d",
+        counters.excluded_by_name,
+        counters.excluded_by_pattern,
+    )
+    if failed_count:
+        logging.info("  Failed files: %d", failed_count)
+    if counters.escaped_symlinks:
+        logging.info("  Symbolic links escaping the root: %d", len(counters.escaped_symlinks))
+    if counters.walk_errors:
+        logging.info("  Unreadable directories: %d", len(counters.walk_errors))
     if cancellation_token and cancellation_token.is_cancellation_requested():
         logging.info("  Analysis was stopped before completion")
     logging.info(
@@ -270,6 +1161,278 @@ def generate_directory_chunks(
     yield {"summary": summary}
 
 
+def _echo_list(values: Sequence[str]) -> Union[List[str], Dict[str, int]]:
+    if len(values) > _OPTIONS_ECHO_LIST_LIMIT:
+        return {"count": len(values)}
+    return list(values)
+
+
+def _options_echo(
+    root_paths: Sequence[Path],
+    threads: int,
+    max_file_size: int,
+    hashing_enabled: bool,
+    excluded_folders: Iterable[str],
+    excluded_files: Iterable[str],
+    exclude_patterns: Sequence[str],
+) -> Dict[str, Any]:
+    """Describe the parameters that produced a result, for provenance."""
+    return {
+        "roots": [_absolute_path(root) for root in root_paths],
+        "threads": threads,
+        "max_file_size": max_file_size,
+        "hash_algorithm": "xxhash" if hashing_enabled else None,
+        "excluded_folders": _echo_list(sorted(excluded_folders)),
+        "excluded_files": _echo_list(sorted(excluded_files)),
+        "exclude_patterns": _echo_list(list(exclude_patterns)),
+    }
+
+
+def _dry_run_summary(
+    file_iterator: Iterator[Path],
+    counters: TraversalCounters,
+    cancellation_token: Optional[CancellationToken],
+) -> Dict[str, Any]:
+    """Gather the file list and sum file sizes without reading any content."""
+    total_bytes = 0
+    for file_path in file_iterator:
+        try:
+            total_bytes += file_path.stat().st_size
+        except OSError as e:
+            logging.warning(f"Could not stat {file_path}: {e}")
+
+    total_files = counters.included + counters.excluded
+    excluded_percentage = (counters.excluded / total_files * 100) if total_files else 0.0
+    stopped_early = bool(cancellation_token and cancellation_token.is_cancellation_requested())
+
+    logging.info("Dry run summary:")
+    logging.info("  Files to process: %d (%d bytes)", counters.included, total_bytes)
+    logging.info("  Excluded files: %d (%.2f%%)", counters.excluded, excluded_percentage)
+
+    return {
+        "dry_run": True,
+        "total_files": total_files,
+        "total_bytes": total_bytes,
+        "excluded_files": counters.excluded,
+        "excluded_by_name": counters.excluded_by_name,
+        "excluded_by_pattern": counters.excluded_by_pattern,
+        "included_files": counters.included,
+        "excluded_percentage": excluded_percentage,
+        "stopped_early": stopped_early,
+        "escaped_symlinks": counters.escaped_symlinks,
+        "walk_errors": counters.walk_errors,
+        "excluded_breakdown": dict(counters.excluded_reasons),
+    }
+
+
+class _RootSet:
+    """Maps gathered files back to the root they were found under."""
+
+    def __init__(self, root_dir: Union[Path, Sequence[Path]], canonicalize: bool = False) -> None:
+        if isinstance(root_dir, (str, os.PathLike)):
+            self.paths: List[Path] = [_canonical_root(root_dir) if canonicalize else Path(root_dir)]
+            self.labels: Optional[List[str]] = None
+        else:
+            self.paths = [_canonical_root(root) if canonicalize else Path(root) for root in root_dir]
+            if canonicalize:
+                self.paths = list(dict.fromkeys(self.paths))
+            names = [path.name for path in self.paths]
+            self.labels = [
+                name if name and names.count(name) == 1 else str(index)
+                for index, name in enumerate(names)
+            ]
+        # Deeper roots first, so nested roots claim their own files.
+        self._lookup = sorted(
+            range(len(self.paths)), key=lambda index: len(self.paths[index].parts), reverse=True
+        )
+
+    def _locate(self, file_path: Path) -> Tuple[Path, Optional[str]]:
+        if self.labels is None:
+            return self.paths[0], None
+        for index in self._lookup:
+            root = self.paths[index]
+            if file_path == root or root in file_path.parents:
+                return root, self.labels[index]
+        return self.paths[0], self.labels[0]
+
+    def parent(self, file_path: Path) -> str:
+        root, label = self._locate(file_path)
+        parent = _normalize_parent(root, file_path)
+        if label is None:
+            return parent
+        return f"{label}/{parent}" if parent else label
+
+    def relative(self, file_path: Path) -> str:
+        return _compose_relative_path(self.parent(file_path), file_path.name)
+
+
+def _canonical_root(root: Union[str, "os.PathLike[str]"]) -> Path:
+    try:
+        return Path(root).resolve(strict=True)
+    except OSError as e:
+        raise RootNotFoundError(f"Cannot canonicalize root directory {root}: {e}") from e
+
+
+_SORT_KEYS: Dict[str, Callable[[_RootSet, Path], Any]] = {
+    "name": lambda roots, path: path.name,
+    "path": lambda roots, path: roots.relative(path),
+    "size": lambda roots, path: _stat_or_zero(path, "st_size"),
+    "mtime": lambda roots, path: _stat_or_zero(path, "st_mtime_ns"),
+}
+
+
+# Keys receive an entry together with its hex digest, which is known even
+# when ``file_hash`` is not part of the output.
+_FINAL_SORT_KEYS: Dict[str, Callable[[Dict[str, Any], Optional[str]], Any]] = {
+    "name": lambda entry, _: entry["filename"],
+    "path": lambda entry, _: entry["relative_path"],
+    "size": lambda entry, _: entry["info"].get("size") or 0,
+    "modified": lambda entry, _: entry["info"].get("modified_ns") or 0,
+    "content_length": lambda entry, _: _content_size(entry["info"].get("content")),
+    "hash": lambda _, hex_digest: hex_digest or "",
+}
+
+
+def _final_sorter(
+    final_sort: str,
+) -> Callable[[List[Tuple[Dict[str, Any], Optional[str]]]], List[Dict[str, Any]]]:
+    """Return a function sorting processed entries by ``final_sort``.
+
+    The function takes ``(entry, hex digest)`` pairs and returns the sorted
+    entries. A leading ``-`` sorts in descending order; ties are always
+    broken by the relative path in ascending order.
+    """
+    descending = final_sort.startswith("-")
+    name = final_sort[1:] if descending else final_sort
+    if name not in _FINAL_SORT_KEYS:
+        raise ValueError(f"Unsupported final sort order: {final_sort}")
+    key = _FINAL_SORT_KEYS[name]
+
+    def sort_entries(entries: List[Tuple[Dict[str, Any], Optional[str]]]) -> List[Dict[str, Any]]:
+        # Two stable sorts keep the path tie-break ascending in both directions.
+        ordered = sorted(entries, key=lambda pair: pair[0]["relative_path"])
+        ordered.sort(key=lambda pair: key(*pair), reverse=descending)
+        return [entry for entry, _ in ordered]
+
+    return sort_entries
+
+
+def _stat_or_zero(file_path: Path, attribute: str) -> int:
+    try:
+        return getattr(file_path.stat(), attribute)
+    except OSError:
+        return 0
+
+
+def _sort_files(
+    roots: _RootSet,
+    files: Iterator[Path],
+    sort_by: str,
+    group_by_parent: bool,
+) -> List[Path]:
+    if sort_by not in _SORT_KEYS:
+        raise ValueError(f"Unsupported sort order: {sort_by}")
+    key = _SORT_KEYS[sort_by]
+
+    def sort_key(file_path: Path) -> Tuple[Any, ...]:
+        # The relative path breaks ties so that the order is fully deterministic.
+        ordered = (key(roots, file_path), roots.relative(file_path))
+        if group_by_parent:
+            # Keep files of a directory together so groups can still be completed.
+            return (roots.parent(file_path),) + ordered
+        return ordered
+
+    return sorted(files, key=sort_key)
+
+
+def _normalize_extension(extension: str) -> str:
+    """Return ``extension`` lower-cased and with a leading dot."""
+    extension = extension.strip().lower()
+    return extension if extension.startswith(".") else f".{extension}"
+
+
+def _content_size(content: Any) -> int:
+    if isinstance(content, str):
+        return len(content.encode("utf-8"))
+    if isinstance(content, (bytes, bytearray)):
+        return len(content)
+    return 0
+
+
+def _passes_filter(
+    filter_callback: Callable[[Dict[str, Any]], bool],
+    entry: Dict[str, Any],
+) -> bool:
+    try:
+        return bool(filter_callback(entry))
+    except Exception:
+        logging.exception("Filter callback failed for %s; keeping entry", entry.get("relative_path"))
+        return True
+
+
+def _enrich_entry(
+    enrich_callback: Callable[[Dict[str, Any]], Optional[Dict[str, Any]]],
+    entry: Dict[str, Any],
+) -> bool:
+    """Merge the callback's extra keys into ``entry``; False if it raised."""
+    try:
+        extra = enrich_callback(entry)
+    except Exception as exc:
+        logging.exception("Enrich callback failed for %s", entry.get("relative_path"))
+        entry["info"] = {
+            "type": "error",
+            "reason": "enrich_callback",
+            "content": f"Enrich callback failed: {exc}",
+            "exception_type": type(exc).__name__,
+            "exception_message": str(exc),
+        }
+        return False
+    if extra:
+        entry.update(extra)
+    return True
+
+
+class _FileTimeout(Exception):
+    """Raised by a worker whose file was not processed within ``file_timeout_ms``."""
+
+
+def _run_with_deadline(timeout: float, fn: Callable[..., Any], *args: Any, **kwargs: Any) -> Any:
+    """Run ``fn`` on a daemon thread and wait at most ``timeout`` seconds for it.
+
+    A blocked read cannot be interrupted, so on timeout the thread is left
+    behind and its result discarded, while the calling worker is freed for
+    the next file. Being a daemon, the thread does not keep the process alive.
+    """
+    outcome: Dict[str, Any] = {}
+
+    def _target() -> None:
+        try:
+            outcome["result"] = fn(*args, **kwargs)
+        except BaseException as e:  # re-raised in the calling worker
+            outcome["error"] = e
+
+    thread = threading.Thread(target=_target, name="samuraizer-file", daemon=True)
+    thread.start()
+    thread.join(timeout)
+    if thread.is_alive():
+        raise _FileTimeout()
+    if "error" in outcome:
+        raise outcome["error"]
+    return outcome["result"]
+
+
+def _compose_relative_path(parent: str, filename: str) -> str:
+    return f"{parent}/{filename}" if parent else filename
+
+
+def _absolute_path(file_path: Path) -> str:
+    try:
+        return str(file_path.resolve(strict=True))
+    except (OSError, RuntimeError):
+        # Broken or looping symlinks cannot be canonicalised.
+        return str(file_path.absolute())
+
+
 def _normalize_parent(root_dir: Path, file_path: Path) -> str:
     try:
         relative_parent = file_path.parent.relative_to(root_dir)
@@ -1,15 +1,17 @@
 """Streaming helpers for repository traversal."""
 
-from typing import Generator, Dict, Any, Set, Optional, List
+from typing import Callable, Generator, Dict, Any, Sequence, Set, Optional, List, Union
 from pathlib import Path
 import logging
 
+from .traversal_core import TraversalProgress
 from .traversal_processor import generate_directory_chunks, _DEFAULT_CHUNK_SIZE
 from ...services.event_service.cancellation import CancellationToken
+from ...services.pattern_service import CompiledFilters
 
 
 def get_directory_structure_stream(
-    root_dir: Path,
+    root_dir: Union[Path, Sequence[Path]],
     max_file_size: int,
     include_binary: bool,
     excluded_folders: Set[str],
@@ -23,6 +25,59 @@ def get_directory_structure_stream(
     cancellation_token: Optional[CancellationToken] = None,
     chunk_size: int = _DEFAULT_CHUNK_SIZE,
     max_pending_tasks: Optional[int] = None,
+    follow_file_symlinks: bool = False,
+    ignore_files: Optional[List[str]] = None,
+    file_timeout_ms: Optional[int] = None,
+    verify_hash: bool = False,
+    binary_preview_bytes: Optional[int] = None,
+    binary_content: bool = True,
+    hash_encoding: Optional[str] = None,
+    quick_hash: bool = False,
+    group_by_parent: bool = False,
+    textual_mime_prefixes: Optional[List[str]] = None,
+    textual_mime_types: Optional[List[str]] = None,
+    dry_run: bool = False,
+    absolute_paths: bool = False,
+    filter_callback: Optional[Callable[[Dict[str, Any]], bool]] = None,
+    parse_structured: bool = False,
+    sort_by: Optional[str] = None,
+    encoding_sample_bytes: Optional[int] = None,
+    strict_decode: bool = False,
+    skip_hidden: bool = False,
+    dockerignore_path: Optional[Path] = None,
+    max_total_content_bytes: Optional[int] = None,
+    decompress: bool = False,
+    progress: Optional[TraversalProgress] = None,
+    content_first: bool = False,
+    include_mime: bool = False,
+    filters: Optional[CompiledFilters] = None,
+    strict_patterns: bool = False,
+    canonicalize_root: bool = False,
+    compress_binary: bool = False,
+    include_xattrs: bool = False,
+    magic_overrides: Optional[List[Dict[str, Any]]] = None,
+    doc_hint: bool = False,
+    encoding_by_extension: Optional[Dict[str, str]] = None,
+    on_error: str = "continue",
+    final_sort: Optional[str] = None,
+    preview_hash: bool = False,
+    expand_archives: bool = False,
+    hybrid_preview: bool = False,
+    gather_threads: Optional[int] = None,
+    cache_db_path: Optional[Union[str, Path]] = None,
+    changed_since_cache: bool = False,
+    line_count_only: bool = False,
+    enrich_callback: Optional[Callable[[Dict[str, Any]], Optional[Dict[str, Any]]]] = None,
+    url_encode_paths: bool = False,
+    stream_failures: bool = False,
+    max_failed_files: Optional[int] = None,
+    max_open_files: Optional[int] = None,
+    reencode_to: Optional[str] = None,
+    single_pass_hash: bool = False,
+    path_prefix: Optional[str] = None,
+    include_empty_dirs: bool = False,
+    include_hex_digest: bool = False,
+    io_concurrency: Optional[int] = None,
 ) -> Generator[Dict[str, Any], None, None]:
     """Yield traversal results as soon as they are available."""
 
@@ -44,6 +99,59 @@ def get_directory_structure_stream(
         cancellation_token=cancellation_token,
         chunk_size=chunk_size,
         max_pending_tasks=max_pending_tasks,
+        follow_file_symlinks=follow_file_symlinks,
+        ignore_files=ignore_files,
+        file_timeout_ms=file_timeout_ms,
+        verify_hash=verify_hash,
+        binary_preview_bytes=binary_preview_bytes,
+        binary_content=binary_content,
+        hash_encoding=hash_encoding,
+        quick_hash=quick_hash,
+        group_by_parent=group_by_parent,
+        textual_mime_prefixes=textual_mime_prefixes,
+        textual_mime_types=textual_mime_types,
+        dry_run=dry_run,
+        absolute_paths=absolute_paths,
+        filter_callback=filter_callback,
+        parse_structured=parse_structured,
+        sort_by=sort_by,
+        encoding_sample_bytes=encoding_sample_bytes,
+        strict_decode=strict_decode,
+        skip_hidden=skip_hidden,
+        dockerignore_path=dockerignore_path,
+        max_total_content_bytes=max_total_content_bytes,
+        decompress=decompress,
+        progress=progress,
+        content_first=content_first,
+        include_mime=include_mime,
+        filters=filters,
+        strict_patterns=strict_patterns,
+        canonicalize_root=canonicalize_root,
+        compress_binary=compress_binary,
+        include_xattrs=include_xattrs,
+        magic_overrides=magic_overrides,
+        doc_hint=doc_hint,
+        encoding_by_extension=encoding_by_extension,
+        on_error=on_error,
+        final_sort=final_sort,
+        preview_hash=preview_hash,
+        expand_archives=expand_archives,
+        hybrid_preview=hybrid_preview,
+        gather_threads=gather_threads,
+        cache_db_path=cache_db_path,
+        changed_since_cache=changed_since_cache,
+        line_count_only=line_count_only,
+        enrich_callback=enrich_callback,
+        url_encode_paths=url_encode_paths,
+        stream_failures=stream_failures,
+        max_failed_files=max_failed_files,
+        max_open_files=max_open_files,
+        reencode_to=reencode_to,
+        single_pass_hash=single_pass_hash,
+        path_prefix=path_prefix,
+        include_empty_dirs=include_empty_dirs,
+        include_hex_digest=include_hex_digest,
+        io_concurrency=io_concurrency,
     )
 
     for payload in chunk_generator:
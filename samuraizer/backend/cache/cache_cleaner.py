@@ -3,7 +3,6 @@
 import logging
 from pathlib import Path
 from typing import Any, Dict, Set
-import sqlite3
 import time
 
 from samuraizer.backend.services.config_services import (
@@ -12,16 +11,20 @@ from samuraizer.backend.services.config_services import (
     get_default_cache_settings,
 )
 from .connection_pool import (
+    cache_available,
     close_all_connections,
     get_connection_context,
     initialize_connection_pool,
     is_cache_disabled,
+    sqlite3,
 )
 
 logger = logging.getLogger(__name__)
 
 def check_and_vacuum_if_needed(db_path: Path) -> None:
     """Check if cache size exceeds max limit and vacuum if needed."""
+    if not cache_available():
+        return
     try:
         cache_settings = get_default_cache_settings()
         max_cache_size = int(cache_settings.get("size_limit_mb", 1000) or 1000)
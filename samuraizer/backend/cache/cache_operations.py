@@ -1,7 +1,11 @@
+from __future__ import annotations
+
 import json
 import logging
-from typing import Dict, Any, Optional
-from sqlite3 import Connection
+from typing import TYPE_CHECKING, Dict, Any, List, Optional
+
+if TYPE_CHECKING:
+    from sqlite3 import Connection
 
 from .connection_pool import is_cache_disabled, queue_write
 
@@ -27,7 +31,7 @@ def get_cached_entry(conn: Connection, file_path: str) -> Optional[Dict[str, Any
         cursor = conn.cursor()
         cursor.execute(
             """
-            SELECT file_hash, file_info, size, mtime
+            SELECT file_hash, file_info, size, mtime, options
             FROM cache WHERE file_path = ?
             """,
             (file_path,),
@@ -35,7 +39,7 @@ def get_cached_entry(conn: Connection, file_path: str) -> Optional[Dict[str, Any
 
         result = cursor.fetchone()
         if result:
-            file_hash, file_info_json, size, mtime = result
+            file_hash, file_info_json, size, mtime, options = result
             try:
                 file_info = json.loads(file_info_json)
                 logger.debug(f"Cache hit for file: {file_path}")
@@ -43,7 +47,8 @@ def get_cached_entry(conn: Connection, file_path: str) -> Optional[Dict[str, Any
                     "file_hash": file_hash,
                     "file_info": file_info,
                     "size": size,
-                    "mtime": mtime
+                    "mtime": mtime,
+                    "options": options,
                 }
             except json.JSONDecodeError:
                 logger.error(f"Failed to decode cached file info for {file_path}")
@@ -55,6 +60,26 @@ def get_cached_entry(conn: Connection, file_path: str) -> Optional[Dict[str, Any
         logger.error(f"Error retrieving cache entry: {e}")
         return None
 
+def list_cached_paths(conn: Connection) -> List[str]:
+    """
+    List the paths of all files stored in the cache.
+
+    Args:
+        conn (Connection): SQLite connection
+
+    Returns:
+        List[str]: The cached file paths, or an empty list if they cannot be read
+    """
+    if conn is None:
+        return []
+
+    try:
+        cursor = conn.execute("SELECT file_path FROM cache")
+        return [file_path for (file_path,) in cursor]
+    except Exception as e:
+        logger.error(f"Error listing cached paths: {e}")
+        return []
+
 def set_cached_entry(
     conn: Connection,
     file_path: str,
@@ -63,6 +88,7 @@ def set_cached_entry(
     size: int,
     mtime: float,
     synchronous: bool = False,
+    options: Optional[str] = None,
 ) -> None:
     """
     Queue a cache entry for batch processing.
@@ -75,13 +101,15 @@ def set_cached_entry(
         size (int): File size
         mtime (float): File modification time
         synchronous (bool): Whether to block until the entry is persisted
+        options (Optional[str]): Fingerprint of the options ``file_info`` was
+            produced with; readers treat a different fingerprint as a miss
     """
     try:
         # Convert file_info to JSON string
         file_info_json = json.dumps(file_info)
 
         # Create entry tuple for batch processing
-        entry = (file_path, file_hash, file_info_json, size, mtime)
+        entry = (file_path, file_hash, file_info_json, size, mtime, options)
 
         if is_cache_disabled():
             logger.debug("Skipping cache persist for %s (cache disabled)", file_path)
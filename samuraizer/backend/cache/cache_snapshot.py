@@ -0,0 +1,125 @@
+"""Comparison of a traversal against the rows stored in a cache database."""
+
+from __future__ import annotations
+
+import logging
+import os
+from contextlib import closing
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Dict, Iterable, List, Optional, Set, Union
+
+from .cache_operations import list_cached_paths
+from .connection_pool import cache_available, sqlite3
+
+logger = logging.getLogger(__name__)
+
+
+@dataclass(frozen=True)
+class CachedState:
+    """What the cache remembers about one file."""
+
+    file_hash: Optional[str]
+    size: int
+    mtime: float
+
+
+class CacheSnapshot:
+    """The cached state of every file, read once before a traversal starts.
+
+    The traversal itself keeps updating the cache, so the rows have to be
+    captured up front to tell what changed since the previous run.
+    """
+
+    def __init__(self, rows: Dict[str, CachedState]) -> None:
+        self.rows = rows
+
+    @classmethod
+    def load(cls, db_path: Union[str, Path]) -> CacheSnapshot:
+        """Read all rows of the cache database at ``db_path``.
+
+        A missing or unreadable database yields an empty snapshot, so every
+        file counts as added.
+        """
+        path = Path(db_path)
+        if not path.exists() or not cache_available():
+            logger.warning(f"Cache database {path} cannot be read; treating all files as added")
+            return cls({})
+        try:
+            with closing(_connect_read_only(path)) as conn:
+                cursor = conn.execute("SELECT file_path, file_hash, size, mtime FROM cache")
+                rows = {
+                    file_path: CachedState(file_hash, size, mtime)
+                    for file_path, file_hash, size, mtime in cursor
+                }
+        except sqlite3.Error as e:
+            logger.error(f"Could not read cache database {path}: {e}")
+            return cls({})
+        return cls(rows)
+
+    def change(self, file_path: Path, hex_digest: Optional[str] = None) -> Optional[str]:
+        """Return ``"added"``, ``"modified"`` or None for an unchanged file.
+
+        The content hash decides when both it and a cached hash are known;
+        otherwise the file counts as modified when its size or mtime differ.
+        """
+        cached = self.rows.get(str(file_path.resolve()))
+        if cached is None:
+            return "added"
+        if hex_digest is not None and cached.file_hash:
+            return None if hex_digest == cached.file_hash else "modified"
+        try:
+            stat = file_path.stat()
+        except OSError:
+            return "modified"
+        if stat.st_size != cached.size or stat.st_mtime != cached.mtime:
+            return "modified"
+        return None
+
+    def deleted(self, seen: Set[str], roots: Iterable[Path]) -> List[str]:
+        """Return the cached paths below ``roots`` that no longer exist."""
+
+        return _deleted_paths(self.rows, seen, roots)
+
+
+def _connect_read_only(path: Path) -> sqlite3.Connection:
+    return sqlite3.connect(f"{path.resolve().as_uri()}?mode=ro", uri=True)
+
+
+def _deleted_paths(cached_paths: Iterable[str], seen: Set[str], roots: Iterable[Path]) -> List[str]:
+    # Cached files that were merely not seen (e.g. because they are now
+    # excluded) still exist and are not reported.
+    prefixes = [str(root.resolve()).rstrip(os.sep) + os.sep for root in roots]
+    return sorted(
+        file_path
+        for file_path in cached_paths
+        if file_path not in seen
+        and file_path.startswith(tuple(prefixes))
+        and not os.path.exists(file_path)
+    )
+
+
+def find_deleted_files(db_path: Union[str, Path], seen: Set[str], roots: Iterable[Path]) -> List[str]:
+    """Return the files cached in ``db_path`` below ``roots`` that no longer exist.
+
+    Args:
+        db_path (Union[str, Path]): The cache database.
+        seen (Set[str]): Resolved paths of the files found by the traversal.
+        roots (Iterable[Path]): The traversal roots.
+
+    Returns:
+        List[str]: The deleted paths, sorted; empty if the database cannot be read.
+    """
+    path = Path(db_path)
+    if not path.exists() or not cache_available():
+        return []
+    try:
+        with closing(_connect_read_only(path)) as conn:
+            cached_paths = list_cached_paths(conn)
+    except sqlite3.Error as e:
+        logger.error(f"Could not read cache database {path}: {e}")
+        return []
+    return _deleted_paths(cached_paths, seen, roots)
+
+
+__all__ = ["CacheSnapshot", "CachedState", "find_deleted_files"]
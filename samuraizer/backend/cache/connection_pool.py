@@ -1,10 +1,11 @@
 # samuraizer/backend/cache/connection_pool.py
 
+from __future__ import annotations
+
 import asyncio
 import atexit
 import logging
 import queue
-import sqlite3
 import sys
 import threading
 import time
@@ -14,6 +15,11 @@ from typing import Any, AsyncGenerator, Dict, Generator, List, Optional, Tuple
 
 from .cache_state import CacheStateManager
 
+try:  # pragma: no cover - only missing from Python builds without SQLite
+    import sqlite3
+except ImportError:  # pragma: no cover - the cache stays disabled
+    sqlite3 = None  # type: ignore[assignment]
+
 PendingWrite = Tuple[Any, ...]
 
 logger = logging.getLogger(__name__)
@@ -23,6 +29,16 @@ class CacheIntegrityError(Exception):
     """Raised when the cache database fails an integrity check."""
 
 
+def cache_available() -> bool:
+    """
+    Return whether the :mod:`sqlite3` module backing the cache is available.
+
+    Python builds without SQLite (e.g. minimal embedded interpreters) can
+    still run analyses, but the cache stays disabled whatever the settings say.
+    """
+    return sqlite3 is not None
+
+
 def calculate_pool_size(thread_count: int) -> int:
     """
     Calculate optimal connection pool size based on thread count.
@@ -268,8 +284,8 @@ class ConnectionPool:
             cursor.executemany(
                 """
                 INSERT OR REPLACE INTO cache
-                (file_path, file_hash, file_info, size, mtime)
-                VALUES (?, ?, ?, ?, ?)
+                (file_path, file_hash, file_info, size, mtime, options)
+                VALUES (?, ?, ?, ?, ?, ?)
                 """,
                 batch,
             )
@@ -479,10 +495,16 @@ class ConnectionPool:
                             file_hash TEXT,
                             file_info TEXT,
                             size INTEGER,
-                            mtime REAL
+                            mtime REAL,
+                            options TEXT
                         )
                         """
                     )
+                    columns = {row[1] for row in conn.execute("PRAGMA table_info(cache)")}
+                    if "options" not in columns:
+                        # Databases from before options were recorded; their
+                        # rows match no options and are refreshed on access.
+                        conn.execute("ALTER TABLE cache ADD COLUMN options TEXT")
 
                     conn.commit()
 
@@ -678,6 +700,9 @@ def initialize_connection_pool(
     force_disable_cache: bool = False
 ) -> None:
     global _connection_pool_instance
+    if not cache_available():
+        logger.warning("The sqlite3 module is not available; caching is disabled.")
+        force_disable_cache = True
     desired_state = force_disable_cache or CacheStateManager.is_disabled()
     CacheStateManager.set_disabled(desired_state)
     with _pool_lock:
@@ -745,7 +770,7 @@ def close_all_connections(
 
 
 def set_cache_disabled(disabled: bool) -> None:
-    CacheStateManager.set_disabled(disabled)
+    CacheStateManager.set_disabled(disabled or not cache_available())
 
 
 def is_cache_disabled() -> bool:
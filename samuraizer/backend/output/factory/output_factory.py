@@ -10,6 +10,7 @@ from ..formatters.dot.dot_output import output_to_dot
 from ..formatters.csv.csv_output import output_to_csv
 from ..formatters.sexp.s_expression_output import output_to_sexp
 from ..formatters.msgpack.msgpack_output import output_to_msgpack, output_to_msgpack_stream
+from ..formatters.arrow.arrow_output import output_to_arrow
 
 class OutputFactory:
     """
@@ -32,6 +33,7 @@ class OutputFactory:
         "sexp": output_to_sexp,
         "msgpack": output_to_msgpack,
         "msgpack_stream": output_to_msgpack_stream,
+        "arrow": output_to_arrow,
     }
 
     @classmethod
@@ -0,0 +1,196 @@
+# samuraizer/output/formatters/arrow/arrow_output.py
+
+"""Columnar Apache Arrow output for loading results into pandas or polars."""
+
+from __future__ import annotations
+
+import logging
+from typing import Any, Dict, Generator, Iterable, Iterator, List, Optional, Tuple
+
+from colorama import Fore, Style
+
+try:  # pragma: no cover - optional dependency (``pip install samuraizer[arrow]``)
+    import pyarrow
+    import pyarrow.ipc
+except ImportError:  # pragma: no cover - Arrow output is unavailable
+    pyarrow = None  # type: ignore[assignment]
+
+# Only per-file metadata is columnar; previews and other variable fields stay
+# in the dict-based formats.
+ARROW_COLUMNS = ("path", "size", "mtime", "hash", "is_binary", "mime", "encoding")
+DEFAULT_BATCH_SIZE = 65536
+
+
+def arrow_available() -> bool:
+    """Return whether the optional ``pyarrow`` package is installed."""
+
+    return pyarrow is not None
+
+
+def _require_pyarrow() -> None:
+    if pyarrow is None:
+        raise RuntimeError(
+            "Arrow output requires the optional pyarrow package (pip install samuraizer[arrow])"
+        )
+
+
+def arrow_schema() -> "pyarrow.Schema":
+    """Return the schema of the record batches; ``mtime`` is in UTC nanoseconds."""
+
+    _require_pyarrow()
+    return pyarrow.schema(
+        [
+            ("path", pyarrow.string()),
+            ("size", pyarrow.int64()),
+            ("mtime", pyarrow.timestamp("ns", tz="UTC")),
+            ("hash", pyarrow.string()),
+            ("is_binary", pyarrow.bool_()),
+            ("mime", pyarrow.string()),
+            ("encoding", pyarrow.string()),
+        ]
+    )
+
+
+def entry_row(path: str, info: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    Return the columnar fields of one entry.
+
+    ``is_binary`` is only set for text and binary entries, and ``encoding``
+    only for text entries; fields an entry does not carry are None. ``hash``
+    prefers the hex ``hex_digest`` (see ``include_hex_digest`` in
+    :func:`generate_directory_chunks`) over the encoded ``file_hash``.
+
+    Args:
+        path: The entry's path relative to the root
+        info: The entry's file information
+
+    Returns:
+        Dict[str, Any]: One value per name in ``ARROW_COLUMNS``
+    """
+    entry_type = info.get("type")
+    return {
+        "path": path,
+        "size": info.get("size"),
+        "mtime": info.get("modified_ns"),
+        "hash": info.get("hex_digest", info.get("file_hash")),
+        "is_binary": {"binary": True, "text": False}.get(entry_type),
+        "mime": info.get("mime_type"),
+        "encoding": info.get("encoding") if entry_type == "text" else None,
+    }
+
+
+def iter_record_batches(
+    records: Iterable[Tuple[str, Dict[str, Any]]],
+    batch_size: int = DEFAULT_BATCH_SIZE,
+) -> Iterator["pyarrow.RecordBatch"]:
+    """
+    Build Arrow record batches of at most ``batch_size`` rows from entries.
+
+    Args:
+        records: ``(path, info)`` pairs
+        batch_size: Maximum number of rows per batch
+
+    Returns:
+        Iterator[pyarrow.RecordBatch]: Batches with the schema of :func:`arrow_schema`
+    """
+    _require_pyarrow()
+    schema = arrow_schema()
+    batch_size = max(1, batch_size)
+    columns: Dict[str, List[Any]] = {name: [] for name in ARROW_COLUMNS}
+
+    def _flush() -> "pyarrow.RecordBatch":
+        arrays = [pyarrow.array(columns[field.name], type=field.type) for field in schema]
+        return pyarrow.RecordBatch.from_arrays(arrays, schema=schema)
+
+    for path, info in records:
+        for name, value in entry_row(path, info).items():
+            columns[name].append(value)
+        if len(columns["path"]) >= batch_size:
+            yield _flush()
+            columns = {name: [] for name in ARROW_COLUMNS}
+    if columns["path"]:
+        yield _flush()
+
+
+def _write_batches(
+    records: Iterable[Tuple[str, Dict[str, Any]]],
+    output_file: str,
+    config: Optional[Dict[str, Any]],
+) -> None:
+    batch_size = int((config or {}).get("arrow_batch_size", DEFAULT_BATCH_SIZE))
+    with pyarrow.ipc.new_file(output_file, arrow_schema()) as writer:
+        for batch in iter_record_batches(records, batch_size):
+            writer.write_batch(batch)
+
+
+def _iter_structure(structure: Dict[str, Any], parent_path: str = "") -> Iterator[Tuple[str, Dict[str, Any]]]:
+    for name, value in structure.items():
+        if not isinstance(value, dict):
+            continue
+        current_path = f"{parent_path}/{name}" if parent_path else name
+        if "type" in value:
+            yield current_path, value
+        else:
+            yield from _iter_structure(value, current_path)
+
+
+def output_to_arrow(
+    data: Dict[str, Any],
+    output_file: str,
+    config: Optional[Dict[str, Any]] = None,
+) -> None:
+    """
+    Write the file entries of a structure to an Arrow IPC file.
+
+    Args:
+        data: The data to write
+        output_file: The output file path
+        config: Optional configuration dictionary (``arrow_batch_size``)
+
+    Raises:
+        RuntimeError: If pyarrow is not installed
+    """
+    _require_pyarrow()
+    try:
+        _write_batches(_iter_structure(data.get("structure", data)), output_file, config)
+    except Exception as e:
+        logging.error(
+            f"{Fore.RED}Error writing Arrow output file: {e}{Style.RESET_ALL}"
+        )
+
+
+def output_to_arrow_stream(
+    data_generator: Generator[Dict[str, Any], None, None],
+    output_file: str,
+    config: Optional[Dict[str, Any]] = None,
+) -> None:
+    """
+    Write file entries to an Arrow IPC file in streaming mode.
+
+    The summary is skipped; only one batch of rows is held in memory.
+
+    Args:
+        data_generator: Generator yielding ``{"path": ..., "info": ...}`` records
+        output_file: The output file path
+        config: Optional configuration dictionary (``arrow_batch_size``)
+
+    Raises:
+        RuntimeError: If pyarrow is not installed
+    """
+    _require_pyarrow()
+
+    def _records() -> Iterator[Tuple[str, Dict[str, Any]]]:
+        for data in data_generator:
+            if not isinstance(data, dict) or "summary" in data:
+                continue
+            if "structure" in data:
+                yield from _iter_structure(data["structure"])
+            else:
+                yield data.get("path", ""), data.get("info", {})
+
+    try:
+        _write_batches(_records(), output_file, config)
+    except Exception as e:
+        logging.error(
+            f"{Fore.RED}Error writing Arrow output file in streaming mode: {e}{Style.RESET_ALL}"
+        )
@@ -83,8 +83,8 @@ def _create_csv_row(path: str, value: Dict[str, Any]) -> List[Any]:
             path,
             value.get("type", ""),
             value.get("size", ""),
-            format_timestamp(value.get("created")),
-            format_timestamp(value.get("modified")),
+            format_timestamp(value.get("created")) or "",
+            format_timestamp(value.get("modified")) or "",
             value.get("permissions", ""),
             value.get("timezone", "UTC")  # Include timezone information
         ]
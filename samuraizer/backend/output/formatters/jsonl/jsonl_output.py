@@ -50,8 +50,10 @@ def output_to_jsonl(
                         )
                         continue
 
-                    file_path = os.path.join(parent, filename) if parent else filename
-                    file_path = file_path.replace(os.sep, "/")
+                    file_path = data.get("relative_path")
+                    if not file_path:
+                        file_path = os.path.join(parent, filename) if parent else filename
+                        file_path = file_path.replace(os.sep, "/")
 
                     payload = {
                         "path": file_path,
@@ -10,6 +10,7 @@ from xml.sax.saxutils import escape as xml_escape
 
 import yaml
 
+from .formatters.arrow.arrow_output import output_to_arrow_stream
 from .formatters.csv.csv_output import output_to_csv_stream
 from .formatters.jsonl.jsonl_output import output_to_jsonl
 from .formatters.msgpack.msgpack_output import output_to_msgpack_stream
@@ -62,6 +63,8 @@ def write_progressive_output(
         _write_msgpack(entries, summary if include_summary else {}, output_file, config)
     elif fmt == "csv":
         _write_csv(entries, output_file, config)
+    elif fmt == "arrow":
+        _write_arrow(entries, output_file, config)
     elif fmt == "yaml":
         _write_yaml(entries, summary if include_summary else {}, output_file, config)
     elif fmt == "xml":
@@ -210,6 +213,13 @@ def _write_csv(entries: EntryIterator, output_file: str, config: Dict[str, objec
     output_to_csv_stream(_generator(), output_file, config)
 
 
+def _write_arrow(entries: EntryIterator, output_file: str, config: Dict[str, object]) -> None:
+    def _generator() -> Generator[Dict[str, object], None, None]:
+        yield from _iter_entry_records(entries)
+
+    output_to_arrow_stream(_generator(), output_file, config)
+
+
 def _write_yaml(entries: EntryIterator, summary: Dict[str, object], output_file: str, config: Dict[str, object]) -> None:
     indent_value = config.get("indent")
     indent = 2
@@ -0,0 +1,162 @@
+"""Support for ``.dockerignore`` files applied to the traversal root."""
+
+from __future__ import annotations
+
+import logging
+import posixpath
+import re
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Iterable, List, Optional, Pattern
+
+from colorama import Fore, Style
+
+logger = logging.getLogger(__name__)
+
+
+@dataclass(frozen=True)
+class DockerIgnoreRule:
+    """A single compiled ``.dockerignore`` line."""
+
+    pattern: str
+    regex: Pattern[str]
+    negated: bool
+
+
+def _translate(pattern: str) -> Pattern[str]:
+    """Translate a cleaned dockerignore pattern into an anchored regex."""
+
+    parts: List[str] = []
+    i = 0
+    length = len(pattern)
+    while i < length:
+        char = pattern[i]
+        if char == "*":
+            if pattern.startswith("**", i):
+                i += 2
+                if pattern.startswith("/", i):
+                    # "**/" also matches zero directories.
+                    parts.append("(?:.*/)?")
+                    i += 1
+                else:
+                    parts.append(".*")
+                continue
+            parts.append("[^/]*")
+        elif char == "?":
+            parts.append("[^/]")
+        elif char == "\\" and i + 1 < length:
+            i += 1
+            parts.append(re.escape(pattern[i]))
+        elif char == "[":
+            end = pattern.find("]", i + 1)
+            if end == -1:
+                parts.append(re.escape(char))
+            else:
+                body = pattern[i + 1 : end]
+                if body.startswith("!"):
+                    body = "^" + body[1:]
+                parts.append(f"[{body}]")
+                i = end
+        else:
+            parts.append(re.escape(char))
+        i += 1
+    return re.compile("^" + "".join(parts) + "$")
+
+
+def _clean(pattern: str) -> str:
+    """Normalise a pattern the way Docker does before matching."""
+
+    cleaned = posixpath.normpath(pattern).lstrip("/")
+    return "" if cleaned == "." else cleaned
+
+
+class DockerIgnore:
+    """
+    Evaluates ``.dockerignore`` rules against paths relative to the build context.
+
+    Unlike gitignore, every pattern is anchored at the context root (a leading
+    ``/`` is optional), ``**`` spans any number of directories and a pattern
+    that matches a directory also excludes everything beneath it.  Rules are
+    evaluated top to bottom and the last matching rule wins, so ``!`` lines
+    can re-include paths excluded by earlier lines.
+    """
+
+    def __init__(self, rules: Iterable[DockerIgnoreRule]) -> None:
+        self.rules = list(rules)
+        self.has_exceptions = any(rule.negated for rule in self.rules)
+
+    @classmethod
+    def parse(cls, lines: Iterable[str]) -> "DockerIgnore":
+        """
+        Compiles the lines of a ``.dockerignore`` file.
+
+        Args:
+            lines (Iterable[str]): Raw file lines; comments and blanks are skipped.
+
+        Returns:
+            DockerIgnore: The compiled matcher.
+        """
+        rules: List[DockerIgnoreRule] = []
+        for raw in lines:
+            line = raw.strip()
+            if not line or line.startswith("#"):
+                continue
+            negated = line.startswith("!")
+            if negated:
+                line = line[1:].strip()
+            pattern = _clean(line)
+            if not pattern:
+                continue
+            rules.append(DockerIgnoreRule(pattern, _translate(pattern), negated))
+        return cls(rules)
+
+    @classmethod
+    def from_file(cls, path: Path) -> Optional["DockerIgnore"]:
+        """
+        Reads and compiles the ``.dockerignore`` file at ``path``.
+
+        Args:
+            path (Path): The ignore file to load.
+
+        Returns:
+            Optional[DockerIgnore]: The matcher, or None if the file is unreadable.
+        """
+        try:
+            lines = path.read_text(encoding="utf-8", errors="replace").splitlines()
+        except OSError as e:
+            logger.warning(
+                f"{Fore.YELLOW}Could not read dockerignore file {path}: {e}{Style.RESET_ALL}"
+            )
+            return None
+        return cls.parse(lines)
+
+    def matches(self, relative_path: str) -> bool:
+        """
+        Checks whether ``relative_path`` is excluded from the build context.
+
+        Args:
+            relative_path (str): A POSIX path relative to the context root.
+
+        Returns:
+            bool: True if the last matching rule excludes the path.
+        """
+        components = relative_path.strip("/").split("/")
+        candidates = ["/".join(components[: depth + 1]) for depth in range(len(components))]
+
+        excluded = False
+        for rule in self.rules:
+            if any(rule.regex.match(candidate) for candidate in candidates):
+                excluded = not rule.negated
+        return excluded
+
+    def prunes_directory(self, relative_path: str) -> bool:
+        """
+        Checks whether traversal can skip the directory at ``relative_path``.
+
+        A matched directory is only pruned when no ``!`` rule exists, since an
+        exception may re-include files below it.
+        """
+        return not self.has_exceptions and self.matches(relative_path)
+
+
+__all__ = ["DockerIgnore", "DockerIgnoreRule"]
@@ -0,0 +1,87 @@
+"""Support for gitignore-style ignore files discovered during traversal."""
+
+from __future__ import annotations
+
+import logging
+from pathlib import Path
+from typing import List, Sequence, Tuple
+
+from colorama import Fore, Style
+from pathspec.patterns import GitWildMatchPattern
+
+logger = logging.getLogger(__name__)
+
+IgnoreLayer = Tuple[Path, List[GitWildMatchPattern]]
+
+
+def load_ignore_layer(directory: Path, file_names: Sequence[str]) -> List[GitWildMatchPattern]:
+    """
+    Reads the ignore files named in ``file_names`` from ``directory``.
+
+    Patterns of all files are concatenated in the order of ``file_names`` so
+    that a later file can override (or negate) rules of an earlier one.
+
+    Args:
+        directory (Path): The directory that may contain ignore files.
+        file_names (Sequence[str]): Names of the ignore files, e.g. ``.gitignore``.
+
+    Returns:
+        List[GitWildMatchPattern]: The compiled patterns (empty if none were found).
+    """
+    patterns: List[GitWildMatchPattern] = []
+    for name in file_names:
+        ignore_file = directory / name
+        if not ignore_file.is_file():
+            continue
+        try:
+            lines = ignore_file.read_text(encoding="utf-8", errors="replace").splitlines()
+        except OSError as e:
+            logger.warning(
+                f"{Fore.YELLOW}Could not read ignore file {ignore_file}: {e}{Style.RESET_ALL}"
+            )
+            continue
+
+        for line in lines:
+            try:
+                pattern = GitWildMatchPattern(line)
+            except Exception as e:
+                logger.error(
+                    f"{Fore.RED}Invalid pattern '{line}' in {ignore_file}: {e}{Style.RESET_ALL}"
+                )
+                continue
+            if pattern.include is not None:
+                patterns.append(pattern)
+    return patterns
+
+
+def is_ignored(layers: Sequence[IgnoreLayer], path: Path, is_dir: bool) -> bool:
+    """
+    Evaluates layered ignore rules for ``path``.
+
+    Layers are ordered from the traversal root down to the directory
+    containing ``path``; rules of deeper layers take precedence, and within a
+    layer the last matching pattern wins, mirroring gitignore semantics.
+
+    Args:
+        layers (Sequence[IgnoreLayer]): ``(base directory, patterns)`` pairs.
+        path (Path): The file or folder to check.
+        is_dir (bool): Whether ``path`` is a directory.
+
+    Returns:
+        bool: True if the path is ignored, otherwise False.
+    """
+    ignored = False
+    for base, patterns in layers:
+        try:
+            relative = path.relative_to(base).as_posix()
+        except ValueError:
+            continue
+        if is_dir:
+            relative += "/"
+        for pattern in patterns:
+            if pattern.regex is not None and pattern.regex.match(relative):
+                ignored = bool(pattern.include)
+    return ignored
+
+
+__all__ = ["IgnoreLayer", "load_ignore_layer", "is_ignored"]
@@ -1,8 +1,9 @@
 import fnmatch
 import logging
+import os
 import re
 from functools import lru_cache
-from typing import Optional, Pattern, Sequence
+from typing import Iterable, List, Optional, Pattern, Sequence, Tuple
 
 from colorama import Fore, Style
 
@@ -30,9 +31,30 @@ def get_exclude_patterns() -> list[str]:
     """
     return _get_config_patterns()
 
+def split_negation(pattern: str) -> Tuple[str, bool]:
+    """
+    Separates the gitignore-style ``!`` prefix from a pattern.
+
+    A leading ``\\!`` stands for a literal ``!`` at the start of a name.
+
+    Args:
+        pattern (str): The pattern as written.
+
+    Returns:
+        Tuple[str, bool]: The pattern without the prefix, and whether it negates.
+    """
+    if pattern.startswith('!'):
+        return pattern[1:], True
+    if pattern.startswith('\\!'):
+        return pattern[1:], False
+    return pattern, False
+
 def matches_patterns(filename: str, patterns: Optional[Sequence[str]] = None) -> bool:
     """
-    Checks if the filename matches any of the patterns (Glob or Regex).
+    Checks if the filename is excluded by the patterns (Glob or Regex).
+
+    Patterns are evaluated in order and the last matching one decides, so a
+    ``!``-prefixed pattern re-includes names excluded by an earlier pattern.
 
     Args:
         filename (str): The name of the file.
@@ -40,23 +62,126 @@ def matches_patterns(filename: str, patterns: Optional[Sequence[str]] = None) ->
             If None, uses patterns from config.
 
     Returns:
-        bool: True if the filename matches any of the patterns, otherwise False.
+        bool: True if the filename is excluded by the patterns, otherwise False.
     """
     if patterns is None:
         patterns = get_exclude_patterns()
 
-    for pattern in patterns:
+    excluded = False
+    for raw_pattern in patterns:
+        pattern, negated = split_negation(raw_pattern)
         if pattern.startswith('regex:'):
             regex: str = pattern[len('regex:'):]
             try:
                 compiled: Pattern[str] = compile_regex(regex)
                 if compiled.match(filename):
-                    return True
+                    excluded = not negated
             except re.error as e:
                 logging.error(
                     f"{Fore.RED}Invalid regex pattern '{regex}': {e}{Style.RESET_ALL}"
                 )
         else:
             if fnmatch.fnmatch(filename, pattern):
+                excluded = not negated
+    return excluded
+
+
+def _has_unterminated_set(pattern: str) -> bool:
+    """Return True if a ``[`` in the glob ``pattern`` is never closed."""
+    i = 0
+    while i < len(pattern):
+        if pattern[i] == '[':
+            j = i + 1
+            if j < len(pattern) and pattern[j] == '!':
+                j += 1
+            if j < len(pattern) and pattern[j] == ']':
+                j += 1
+            close = pattern.find(']', j)
+            if close == -1:
                 return True
+            i = close
+        i += 1
     return False
+
+
+class CompiledFilters:
+    """
+    Exclusion rules compiled once so they can be reused across traversals.
+
+    Glob patterns follow :func:`fnmatch.fnmatch` (including its platform
+    case handling) and ``regex:`` patterns are matched from the start of the
+    name, exactly like :func:`matches_patterns`. Patterns apply in order and
+    the last match decides, so ``!keep.log`` after ``*.log`` keeps that file.
+    Invalid regexes are logged and skipped unless ``strict`` is set.
+
+    Args:
+        excluded_folders (Iterable[str]): Folder names to skip.
+        excluded_files (Iterable[str]): File names to skip.
+        exclude_patterns (Iterable[str]): Glob or ``regex:`` patterns.
+        strict (bool): Raise instead of skipping invalid patterns.
+
+    Raises:
+        ValueError: In strict mode, naming the first invalid pattern: a regex
+            that does not compile or a glob with an unterminated ``[`` set
+            (which :mod:`fnmatch` would otherwise match literally).
+    """
+
+    def __init__(
+        self,
+        excluded_folders: Iterable[str] = (),
+        excluded_files: Iterable[str] = (),
+        exclude_patterns: Iterable[str] = (),
+        strict: bool = False,
+    ) -> None:
+        self.excluded_folders = frozenset(excluded_folders)
+        self.excluded_files = frozenset(excluded_files)
+        self.exclude_patterns: Tuple[str, ...] = tuple(exclude_patterns)
+        # ``(compiled, is_regex, negated)`` in the order the patterns were given.
+        self._rules: List[Tuple[Pattern[str], bool, bool]] = []
+        for raw_pattern in self.exclude_patterns:
+            pattern, negated = split_negation(raw_pattern)
+            if pattern.startswith('regex:'):
+                regex = pattern[len('regex:'):]
+                try:
+                    self._rules.append((compile_regex(regex), True, negated))
+                except re.error as e:
+                    if strict:
+                        raise ValueError(f"Invalid exclusion pattern '{raw_pattern}': {e}") from e
+                    logging.error(
+                        f"{Fore.RED}Invalid regex pattern '{regex}': {e}{Style.RESET_ALL}"
+                    )
+            else:
+                if strict and _has_unterminated_set(pattern):
+                    raise ValueError(
+                        f"Invalid exclusion pattern '{raw_pattern}': unterminated character set"
+                    )
+                self._rules.append(
+                    (re.compile(fnmatch.translate(os.path.normcase(pattern))), False, negated)
+                )
+        self._has_negations = any(negated for _, _, negated in self._rules)
+
+    def matches_pattern(self, name: str) -> bool:
+        """Return True if ``name`` is excluded by the exclusion patterns."""
+        normalized = os.path.normcase(name)
+        if not self._has_negations:
+            return any(
+                compiled.match(name if is_regex else normalized)
+                for compiled, is_regex, _ in self._rules
+            )
+        excluded = False
+        for compiled, is_regex, negated in self._rules:
+            if compiled.match(name if is_regex else normalized):
+                excluded = not negated
+        return excluded
+
+    def excludes_folder(self, name: str) -> bool:
+        """Return True if a folder called ``name`` should be skipped."""
+        return name in self.excluded_folders or self.matches_pattern(name)
+
+    def file_exclusion_reason(self, name: str) -> Optional[str]:
+        """Return ``"file_name"`` or ``"pattern"`` for excluded files, otherwise None."""
+        if name in self.excluded_files:
+            return "file_name"
+        if self.matches_pattern(name):
+            return "pattern"
+        return None
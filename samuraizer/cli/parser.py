@@ -4,7 +4,43 @@ from typing import Optional
 from samuraizer.config.timezone_service import TimezoneService
 
 
-SUPPORTED_FORMATS = ["json", "yaml", "xml", "jsonl", "dot", "csv", "sexp", "msgpack"]
+SUPPORTED_FORMATS = ["json", "yaml", "xml", "jsonl", "dot", "csv", "sexp", "msgpack", "arrow"]
+
+
+def _extension_encoding(value: str) -> tuple:
+    """Parse an ``EXT=ENCODING`` mapping."""
+    extension, _, encoding = value.partition("=")
+    if not extension.strip() or not encoding.strip():
+        raise argparse.ArgumentTypeError(
+            f"Invalid encoding mapping '{value}': expected EXT=ENCODING"
+        )
+    return extension.strip(), encoding.strip()
+
+
+def _positive_int(value: str) -> int:
+    """Parse a count that must be at least 1."""
+    try:
+        number = int(value)
+    except ValueError:
+        raise argparse.ArgumentTypeError(f"Invalid number '{value}'") from None
+    if number < 1:
+        raise argparse.ArgumentTypeError(f"Invalid number '{value}': must be at least 1")
+    return number
+
+
+def _magic_override(value: str) -> dict:
+    """Parse a ``HEXPREFIX=binary|text`` magic override rule."""
+    hex_prefix, _, kind = value.partition("=")
+    if kind not in {"binary", "text"}:
+        raise argparse.ArgumentTypeError(
+            f"Invalid magic override '{value}': expected HEXPREFIX=binary or HEXPREFIX=text"
+        )
+    try:
+        if not bytes.fromhex(hex_prefix):
+            raise ValueError("empty prefix")
+    except ValueError as exc:
+        raise argparse.ArgumentTypeError(f"Invalid magic override '{value}': {exc}") from exc
+    return {"hex_prefix": hex_prefix, "is_binary": kind == "binary"}
 
 
 def parse_arguments(argv: Optional[list[str]] = None):
@@ -23,6 +59,7 @@ def parse_arguments(argv: Optional[list[str]] = None):
             "  samuraizer /path/to/repo -o output.sexp --format sexp\n"
             "  samuraizer /path/to/repo -o output.xml --format xml\n"
             "  samuraizer /path/to/repo -o output.msgpack --format msgpack\n"
+            "  samuraizer /path/to/repo -o output.arrow --format arrow\n"
             "  samuraizer /path/to/repo -o output.json --use-utc\n"
             "  samuraizer /path/to/repo -o output.json --repository-timezone America/New_York\n"
         ),
@@ -87,7 +124,23 @@ def parse_arguments(argv: Optional[list[str]] = None):
         action="store_true",
         help="Disable file caching (slower but uses less disk space)",
     )
+    parser.add_argument(
+        "--changed-since-cache",
+        action="store_true",
+        help="Only output files that were added or modified since the cache was last updated.",
+    )
+    parser.add_argument(
+        "--report-deleted",
+        action="store_true",
+        help="List cached files that no longer exist in the summary as deleted_files.",
+    )
 
+    parser.add_argument(
+        "--verify-hash",
+        action="store_true",
+        default=None,
+        help="Re-hash files before trusting cached entries (slower, but ignores unreliable mtimes).",
+    )
     parser.add_argument(
         "--include-summary",
         action="store_true",
@@ -100,6 +153,12 @@ def parse_arguments(argv: Optional[list[str]] = None):
         default=None,
         help="Follows symbolic links during traversal.",
     )
+    parser.add_argument(
+        "--follow-file-symlinks",
+        action="store_true",
+        default=None,
+        help="Reads symbolically linked files whose target lies inside the root directory.",
+    )
     parser.add_argument(
         "--image-extensions",
         nargs="*",
@@ -112,12 +171,82 @@ def parse_arguments(argv: Optional[list[str]] = None):
         default=None,
         help="Glob or regex patterns to exclude files and folders.",
     )
+    parser.add_argument(
+        "--ignore-files",
+        nargs="*",
+        default=None,
+        help="Names of gitignore-style ignore files to honour (e.g. .gitignore .samuraizerignore).",
+    )
+    parser.add_argument(
+        "--textual-mime-prefixes",
+        nargs="*",
+        default=None,
+        help="Additional MIME type prefixes to treat as text (e.g. application/vnd.).",
+    )
+    parser.add_argument(
+        "--textual-mime-types",
+        nargs="*",
+        default=None,
+        help="Additional exact MIME types to treat as text (e.g. application/x-yaml).",
+    )
+    parser.add_argument(
+        "--encoding-by-extension",
+        nargs="*",
+        type=_extension_encoding,
+        default=None,
+        metavar="EXT=ENCODING",
+        help="Read files with the given extension in a fixed encoding (e.g. sjis=shift_jis).",
+    )
+    parser.add_argument(
+        "--magic-overrides",
+        nargs="*",
+        type=_magic_override,
+        default=None,
+        metavar="HEXPREFIX=binary|text",
+        help=(
+            "Classify files whose leading bytes start with HEXPREFIX as binary or text "
+            "before any other detection; the first matching rule wins."
+        ),
+    )
+    parser.add_argument(
+        "--on-error",
+        choices=["continue", "abort"],
+        default="continue",
+        help="Keep going after files that fail to process (default) or abort on the first one.",
+    )
     parser.add_argument(
         "--threads",
-        type=int,
+        type=_positive_int,
         default=None,
         help="Number of threads for parallel processing (default from configuration).",
     )
+    parser.add_argument(
+        "--gather-threads",
+        type=_positive_int,
+        default=None,
+        help="Number of threads listing directories during gathering (default: --threads).",
+    )
+    parser.add_argument(
+        "--io-concurrency",
+        type=_positive_int,
+        default=None,
+        help="Number of files read ahead concurrently; raise for network mounts.",
+    )
+    parser.add_argument(
+        "--max-open-files",
+        type=_positive_int,
+        default=None,
+        help="Maximum number of files processed at once, to stay below the open file limit.",
+    )
+    parser.add_argument(
+        "--max-pending-tasks",
+        type=_positive_int,
+        default=None,
+        help=(
+            "Maximum number of files queued for processing at once "
+            "(default: 4 per worker thread, at least the chunk size)."
+        ),
+    )
     parser.add_argument(
         "--encoding",
         type=str,
@@ -141,9 +270,172 @@ def parse_arguments(argv: Optional[list[str]] = None):
         help="Disables hash verification.",
     )
     parser.add_argument(
-        "--max-size",
+        "--hash-encoding",
+        choices=["hex", "base64", "base64url", "dec"],
+        default=None,
+        help="Include each file's content hash in the output using this encoding.",
+    )
+    parser.add_argument(
+        "--quick-hash",
+        action="store_true",
+        help="Add a cheap hash of each file's size, head and tail for deduplication.",
+    )
+    parser.add_argument(
+        "--preview-hash",
+        action="store_true",
+        help="Add a hash of exactly the previewed content, which differs from the file hash when truncated.",
+    )
+    parser.add_argument(
+        "--line-count-only",
+        action="store_true",
+        help="Report line counts instead of text content, with a breakdown by extension in the summary.",
+    )
+    parser.add_argument(
+        "--include-empty-dirs",
+        action="store_true",
+        help="Add entries for directories that are empty once exclusions apply.",
+    )
+    parser.add_argument(
+        "--path-prefix",
+        default=None,
+        metavar="SUBDIR",
+        help="Only scan SUBDIR (relative to the root) while keeping paths relative to the root.",
+    )
+    parser.add_argument(
+        "--single-pass-hash",
+        action="store_true",
+        help="Hash files from the same reads as their previews instead of opening them twice.",
+    )
+    parser.add_argument(
+        "--reencode-to",
+        default=None,
+        metavar="ENCODING",
+        help="Also output text previews re-encoded to ENCODING (base64, e.g. utf-8 or utf-8-sig).",
+    )
+    parser.add_argument(
+        "--hybrid-preview",
+        action="store_true",
+        help="Stop text previews at a binary tail (e.g. DER after a PEM header) and report its offset.",
+    )
+    parser.add_argument(
+        "--encoding-sample-bytes",
+        type=_positive_int,
+        default=None,
+        help="Number of bytes inspected to detect the encoding of text files (default 512 KiB).",
+    )
+    parser.add_argument(
+        "--strict-decode",
+        action="store_true",
+        help="Report text files with invalid byte sequences as errors instead of replacing them.",
+    )
+    parser.add_argument(
+        "--binary-preview-bytes",
         type=int,
         default=None,
+        help="Maximum number of bytes included in binary file previews (default 3 MiB).",
+    )
+    parser.add_argument(
+        "--no-binary-content",
+        action="store_true",
+        help="Report binary files by metadata only, without reading or encoding their bytes.",
+    )
+    parser.add_argument(
+        "--file-timeout-ms",
+        type=_positive_int,
+        default=None,
+        help="Abort processing of a single file after this many milliseconds.",
+    )
+    parser.add_argument(
+        "--parse-structured",
+        action="store_true",
+        help="Add a parsed preview of JSON, TOML and YAML files to the output.",
+    )
+    parser.add_argument(
+        "--sort-by",
+        choices=["name", "size", "mtime", "path"],
+        default=None,
+        help="Process files in a deterministic order instead of filesystem order.",
+    )
+    parser.add_argument(
+        "--final-sort",
+        choices=[
+            f"{prefix}{key}"
+            for key in ("name", "path", "size", "modified", "content_length", "hash")
+            for prefix in ("", "-")
+        ],
+        default=None,
+        help=(
+            "Sort the processed entries before writing them ('-' for descending). "
+            "Holds all entries in memory, so output is not streamed."
+        ),
+    )
+    parser.add_argument(
+        "--max-total-content-bytes",
+        type=int,
+        default=None,
+        help="Stop including file content once this many content bytes have been emitted.",
+    )
+    parser.add_argument(
+        "--dockerignore",
+        dest="dockerignore_path",
+        type=str,
+        default=None,
+        help="Apply the rules of this .dockerignore file to paths relative to the root directory.",
+    )
+    parser.add_argument(
+        "--strict-patterns",
+        action="store_true",
+        help="Fail on invalid exclusion patterns instead of ignoring them.",
+    )
+    parser.add_argument(
+        "--skip-hidden",
+        action="store_true",
+        help="Skip dotfiles, dot-directories and entries marked hidden on Windows.",
+    )
+    parser.add_argument(
+        "--expand-archives",
+        action="store_true",
+        help="Analyse the files inside zip and tar archives as if the archive were a directory.",
+    )
+    parser.add_argument(
+        "--doc-hint",
+        action="store_true",
+        help="Report doctypes, coding comments and shebangs found on the first line as 'doc_hint'.",
+    )
+    parser.add_argument(
+        "--include-xattrs",
+        action="store_true",
+        help="Record extended attributes of every file as base64 values (Linux only).",
+    )
+    parser.add_argument(
+        "--compress-binary",
+        action="store_true",
+        help="Deflate binary previews before base64 encoding when that makes them smaller.",
+    )
+    parser.add_argument(
+        "--include-mime",
+        action="store_true",
+        help="Record the detected MIME type of every file as 'mime_type'.",
+    )
+    parser.add_argument(
+        "--content-first",
+        action="store_true",
+        help="Classify files by their content and use the extension only as a tiebreaker.",
+    )
+    parser.add_argument(
+        "--decompress",
+        action="store_true",
+        help="Preview the decompressed content of zstd files (requires the 'zstandard' package).",
+    )
+    parser.add_argument(
+        "--dry-run",
+        action="store_true",
+        help="Only count the files that would be processed and their total size.",
+    )
+    parser.add_argument(
+        "--max-size",
+        type=_positive_int,
+        default=None,
         help="Maximum file size to read in MB (default from configuration).",
     )
 
@@ -161,6 +161,11 @@ CONFIG_SCHEMA: Dict[str, Any] = {
             "properties": {
                 "use_utc": {"type": "boolean"},
                 "repository_timezone": {"type": "string", "minLength": 1},
+                "timezone_offset_minutes": {
+                    "type": "integer",
+                    "minimum": -1439,
+                    "maximum": 1439,
+                },
             },
             "required": ["use_utc"],
         },
@@ -17,7 +17,7 @@ _PROFILE_SECTION_KEY_MAP: Dict[str, Set[str]] = {
     "output": {"compression", "streaming", "pretty_print", "path"},
     "cache": {"path", "size_limit_mb", "cleanup_days"},
     "theme": {"name"},
-    "timezone": {"use_utc", "repository_timezone"},
+    "timezone": {"use_utc", "repository_timezone", "timezone_offset_minutes"},
 }
 
 
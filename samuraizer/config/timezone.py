@@ -26,6 +26,17 @@ class TimezoneNormalizer:
                 timezone_cfg["use_utc"] = bool(use_utc_value)
                 corrected = True
 
+            if "timezone_offset_minutes" in timezone_cfg:
+                offset = timezone_cfg["timezone_offset_minutes"]
+                if offset is None or isinstance(offset, bool) or not isinstance(offset, int) or abs(offset) > 1439:
+                    if offset is not None:
+                        logger.warning(
+                            "Ignoring invalid timezone offset %r; expected whole minutes within one day.",
+                            offset,
+                        )
+                    timezone_cfg.pop("timezone_offset_minutes", None)
+                    corrected = True
+
             tz_value = timezone_cfg.get("repository_timezone")
             if tz_value is None:
                 if "repository_timezone" in timezone_cfg:
@@ -1,7 +1,7 @@
 from __future__ import annotations
 
 import logging
-from datetime import datetime, timezone, tzinfo
+from datetime import datetime, timedelta, timezone, tzinfo
 from typing import Any, Dict, List, Optional, Set
 from zoneinfo import ZoneInfo, ZoneInfoNotFoundError, available_timezones
 
@@ -9,6 +9,28 @@ from .unified import UnifiedConfigManager
 
 logger = logging.getLogger(__name__)
 
+# ``datetime.timezone`` only accepts offsets strictly within one day.
+MAX_OFFSET_MINUTES = 24 * 60 - 1
+
+
+def fixed_offset_timezone(minutes: int) -> timezone:
+    """Return a fixed-offset timezone labelled like ``"+05:30"``."""
+    if not -MAX_OFFSET_MINUTES <= minutes <= MAX_OFFSET_MINUTES:
+        raise ValueError(
+            f"Timezone offset must be between -{MAX_OFFSET_MINUTES} and {MAX_OFFSET_MINUTES} minutes"
+        )
+    sign = "-" if minutes < 0 else "+"
+    hours, mins = divmod(abs(minutes), 60)
+    return timezone(timedelta(minutes=minutes), f"{sign}{hours:02d}:{mins:02d}")
+
+
+def _coerce_offset(value: Any) -> Optional[int]:
+    if isinstance(value, bool) or not isinstance(value, int):
+        return None
+    if not -MAX_OFFSET_MINUTES <= value <= MAX_OFFSET_MINUTES:
+        return None
+    return value
+
 
 class TimezoneService:
     """Convenience helper exposing timezone operations on top of the unified config."""
@@ -40,7 +62,11 @@ class TimezoneService:
             repository_tz = repository_tz.strip() or None
         else:
             repository_tz = None
-        return {"use_utc": use_utc, "repository_timezone": repository_tz}
+        return {
+            "use_utc": use_utc,
+            "repository_timezone": repository_tz,
+            "timezone_offset_minutes": _coerce_offset(section.get("timezone_offset_minutes")),
+        }
 
     def _is_timezone_available(self, tz_name: Optional[str]) -> bool:
         if not tz_name:
@@ -99,6 +125,10 @@ class TimezoneService:
                 "Repository timezone '%s' is not available. Falling back to system timezone.",
                 original_tz,
             )
+
+        offset = config.get("timezone_offset_minutes")
+        if offset is not None:
+            return fixed_offset_timezone(offset)
         return self.get_system_timezone()
 
     def get_config(self) -> Dict[str, Any]:
@@ -126,6 +156,14 @@ class TimezoneService:
                 "timezone.use_utc", False, profile=self._active_profile_kw
             )
 
+    def set_timezone_offset(self, minutes: Optional[int]) -> None:
+        """Store a fixed UTC offset used when no named timezone is configured."""
+        if minutes is not None:
+            fixed_offset_timezone(minutes)
+        self._manager.set_value(
+            "timezone.timezone_offset_minutes", minutes, profile=self._active_profile_kw
+        )
+
     def use_utc(self, use_utc: bool = True) -> None:
         self._manager.set_value(
             "timezone.use_utc", use_utc, profile=self._active_profile_kw
@@ -142,4 +180,4 @@ class TimezoneService:
         return sorted(self._available_timezones)
 
 
-__all__ = ["TimezoneService"]
+__all__ = ["MAX_OFFSET_MINUTES", "TimezoneService", "fixed_offset_timezone"]
@@ -48,6 +48,7 @@ class ThemeConfig(TypedDict, total=False):
 class TimezoneConfig(TypedDict, total=False):
     use_utc: bool
     repository_timezone: Optional[str]
+    timezone_offset_minutes: Optional[int]
 
 
 class ConfigurationData(TypedDict, total=False):
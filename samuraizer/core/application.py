@@ -9,6 +9,7 @@ from typing import Optional
 from colorama import init as colorama_init
 
 from samuraizer.backend.analysis.traversal.progressive_store import ProgressiveResultStore
+from samuraizer.backend.analysis.traversal.traversal_core import TraversalAbortedError
 from samuraizer.backend.analysis.traversal.traversal_processor import get_directory_structure
 from samuraizer.backend.analysis.traversal.traversal_stream import (
     get_directory_structure_stream,
@@ -20,6 +21,7 @@ from samuraizer.backend.cache.connection_pool import (
     set_cache_disabled,
 )
 from samuraizer.backend.output.factory.output_factory import OutputFactory
+from samuraizer.backend.output.formatters.arrow.arrow_output import arrow_available
 from samuraizer.backend.output.progressive_writer import write_progressive_output
 from samuraizer.backend.services.config_services import (
     CACHE_DB_FILE,
@@ -34,13 +36,13 @@ from samuraizer.backend.services.config_services import (
 )
 from samuraizer.backend.services.event_service.cancellation import CancellationTokenSource
 from samuraizer.backend.services.logging.logging_service import setup_logging
+from samuraizer.backend.services.pattern_service import CompiledFilters
 from samuraizer.cli.parser import SUPPORTED_FORMATS, parse_arguments
 from samuraizer.config import ConfigError, ConfigValidationError
+from samuraizer.utils.build_info import DEFAULT_THREAD_MULTIPLIER
 from samuraizer.utils.encoding_utils import normalize_encoding_hint
 from samuraizer.config.unified import UnifiedConfigManager
 
-DEFAULT_THREAD_MULTIPLIER = 2
-
 _cli_cancellation_source: Optional[CancellationTokenSource] = None
 
 
@@ -98,6 +100,7 @@ def _prepare_output_format(output_file: str, output_format: str) -> str:
         "csv": ".csv",
         "sexp": ".sexp",
         "msgpack": ".msgpack",
+        "arrow": ".arrow",
     }
     expected_extension = extension_map.get(output_format)
     if expected_extension is None:
@@ -166,6 +169,9 @@ def run() -> None:
     _set_cli_cancellation_source(cancellation_source)
 
     root_directory = Path(args.root_directory).resolve()
+    if not root_directory.is_dir():
+        logging.error("Root directory does not exist or is not a directory: %s", root_directory)
+        sys.exit(1)
     output_file = args.output
 
     analysis_defaults = get_default_analysis_settings()
@@ -177,6 +183,9 @@ def run() -> None:
     if output_format not in SUPPORTED_FORMATS:
         logging.error("Unsupported output format requested: %s", output_format)
         sys.exit(2)
+    if output_format == "arrow" and not arrow_available():
+        logging.error("The arrow format requires the optional pyarrow package (pip install samuraizer[arrow]).")
+        sys.exit(2)
 
     stream_mode = output_defaults.get("streaming", False)
     if args.stream is True:
@@ -192,6 +201,10 @@ def run() -> None:
     if args.follow_symlinks:
         follow_symlinks = True
 
+    follow_file_symlinks = bool(analysis_defaults.get("follow_file_symlinks", False))
+    if args.follow_file_symlinks:
+        follow_file_symlinks = True
+
     raw_encoding = args.encoding if args.encoding is not None else analysis_defaults.get("encoding", "auto")
     encoding = normalize_encoding_hint(raw_encoding)
 
@@ -211,6 +224,10 @@ def run() -> None:
 
     hashing_enabled = cache_enabled
 
+    verify_hash = bool(analysis_defaults.get("verify_hash", False))
+    if args.verify_hash:
+        verify_hash = True
+
     max_size_mb = args.max_size or analysis_defaults.get("max_file_size_mb", 50)
     max_file_size = max_size_mb * 1024 * 1024
 
@@ -229,6 +246,7 @@ def run() -> None:
     excluded_files = get_excluded_files()
     exclude_patterns = get_exclude_patterns()
     image_extensions = get_image_extensions()
+    ignore_files = [str(name) for name in analysis_defaults.get("ignore_files", [])]
 
     if args.exclude_folders:
         excluded_folders.update(args.exclude_folders)
@@ -236,11 +254,19 @@ def run() -> None:
         excluded_files.update(args.exclude_files)
     if args.exclude_patterns:
         exclude_patterns = list(dict.fromkeys(exclude_patterns + args.exclude_patterns))
+    if args.ignore_files:
+        ignore_files = list(dict.fromkeys(ignore_files + args.ignore_files))
     if args.image_extensions:
         additional_image_extensions = {
             ext.lower() if ext.startswith(".") else f".{ext.lower()}" for ext in args.image_extensions
         }
         image_extensions.update(additional_image_extensions)
+    if args.strict_patterns:
+        try:
+            CompiledFilters(excluded_folders, excluded_files, exclude_patterns, strict=True)
+        except ValueError as exc:
+            logging.error("%s", exc)
+            sys.exit(1)
 
     logging.info("Active configuration profile: %s", config_manager.active_profile)
     logging.info("Search the directory: %s", root_directory)
@@ -248,8 +274,14 @@ def run() -> None:
     logging.info("Excluded files: %s", ", ".join(sorted(excluded_files)))
     logging.info("Exclusion pattern: %s", ", ".join(exclude_patterns))
     logging.info("Image file extensions: %s", ", ".join(sorted(image_extensions)))
+    if ignore_files:
+        logging.info("Ignore files: %s", ", ".join(ignore_files))
     logging.info("Binary files %s included", "are" if include_binary else "are not")
     logging.info("Symbolic links are %s", "followed" if follow_symlinks else "not followed")
+    logging.info(
+        "Symbolically linked files are %s",
+        "followed" if follow_file_symlinks else "not treated separately",
+    )
     logging.info("Number of threads: %s", threads)
     if encoding is None:
         logging.info("Standard encoding: auto (automatic detection)")
@@ -259,6 +291,7 @@ def run() -> None:
     logging.info("Include summary: %s", include_summary)
     logging.info("Cache enabled: %s", cache_enabled)
     logging.info("Hashing algorithm: %s", "xxhash" if hashing_enabled else "disabled")
+    logging.info("Verify cached hashes: %s", verify_hash)
     logging.info("Cache path: %s", cache_path)
     logging.info("Max file size (MB): %s", max_size_mb)
     logging.info("Using UTC timestamps: %s", use_utc)
@@ -276,6 +309,11 @@ def run() -> None:
         logging.error("Failed to initialize cache: %s", exc)
         sys.exit(1)
 
+    # Reconciling against the cache resolves every seen path, so only do it on request.
+    reconcile_db_path = (
+        cache_db_path if args.changed_since_cache or args.report_deleted else None
+    )
+
     try:
         if stream_mode:
             if output_format in {"json", "jsonl", "msgpack"}:
@@ -292,6 +330,49 @@ def run() -> None:
                     encoding=encoding,
                     hashing_enabled=hashing_enabled,
                     cancellation_token=cancellation_source.token,
+                    follow_file_symlinks=follow_file_symlinks,
+                    ignore_files=ignore_files,
+                    file_timeout_ms=args.file_timeout_ms,
+                    verify_hash=verify_hash,
+                    binary_preview_bytes=args.binary_preview_bytes,
+                    binary_content=not args.no_binary_content,
+                    hash_encoding=args.hash_encoding,
+                    quick_hash=args.quick_hash,
+                    textual_mime_prefixes=args.textual_mime_prefixes,
+                    textual_mime_types=args.textual_mime_types,
+                    dry_run=args.dry_run,
+                    parse_structured=args.parse_structured,
+                    sort_by=args.sort_by,
+                    encoding_sample_bytes=args.encoding_sample_bytes,
+                    strict_decode=args.strict_decode,
+                    skip_hidden=args.skip_hidden,
+                    dockerignore_path=args.dockerignore_path,
+                    max_total_content_bytes=args.max_total_content_bytes,
+                    decompress=args.decompress,
+                    max_pending_tasks=args.max_pending_tasks,
+                    content_first=args.content_first,
+                    include_mime=args.include_mime,
+                    strict_patterns=args.strict_patterns,
+                    compress_binary=args.compress_binary,
+                    include_xattrs=args.include_xattrs,
+                    magic_overrides=args.magic_overrides,
+                    doc_hint=args.doc_hint,
+                    encoding_by_extension=dict(args.encoding_by_extension or ()),
+                    on_error=args.on_error,
+                    final_sort=args.final_sort,
+                    preview_hash=args.preview_hash,
+                    expand_archives=args.expand_archives,
+                    hybrid_preview=args.hybrid_preview,
+                    gather_threads=args.gather_threads,
+                    cache_db_path=reconcile_db_path,
+                    changed_since_cache=args.changed_since_cache,
+                    line_count_only=args.line_count_only,
+                    max_open_files=args.max_open_files,
+                    reencode_to=args.reencode_to,
+                    single_pass_hash=args.single_pass_hash,
+                    path_prefix=args.path_prefix,
+                    include_empty_dirs=args.include_empty_dirs,
+                    io_concurrency=args.io_concurrency,
                 )
                 output_function = OutputFactory.get_output(
                     output_format, streaming=True, config=output_defaults
@@ -317,6 +398,50 @@ def run() -> None:
                     cancellation_token=cancellation_source.token,
                     chunk_callback=store.add_entries,
                     materialize=False,
+                    follow_file_symlinks=follow_file_symlinks,
+                    ignore_files=ignore_files,
+                    file_timeout_ms=args.file_timeout_ms,
+                    verify_hash=verify_hash,
+                    binary_preview_bytes=args.binary_preview_bytes,
+                    binary_content=not args.no_binary_content,
+                    hash_encoding=args.hash_encoding,
+                    quick_hash=args.quick_hash,
+                    textual_mime_prefixes=args.textual_mime_prefixes,
+                    textual_mime_types=args.textual_mime_types,
+                    dry_run=args.dry_run,
+                    parse_structured=args.parse_structured,
+                    sort_by=args.sort_by,
+                    encoding_sample_bytes=args.encoding_sample_bytes,
+                    strict_decode=args.strict_decode,
+                    skip_hidden=args.skip_hidden,
+                    dockerignore_path=args.dockerignore_path,
+                    max_total_content_bytes=args.max_total_content_bytes,
+                    decompress=args.decompress,
+                    max_pending_tasks=args.max_pending_tasks,
+                    content_first=args.content_first,
+                    include_mime=args.include_mime,
+                    strict_patterns=args.strict_patterns,
+                    compress_binary=args.compress_binary,
+                    include_xattrs=args.include_xattrs,
+                    magic_overrides=args.magic_overrides,
+                    doc_hint=args.doc_hint,
+                    encoding_by_extension=dict(args.encoding_by_extension or ()),
+                    on_error=args.on_error,
+                    final_sort=args.final_sort,
+                    preview_hash=args.preview_hash,
+                    expand_archives=args.expand_archives,
+                    hybrid_preview=args.hybrid_preview,
+                    gather_threads=args.gather_threads,
+                    cache_db_path=reconcile_db_path,
+                    changed_since_cache=args.changed_since_cache,
+                    line_count_only=args.line_count_only,
+                    max_open_files=args.max_open_files,
+                    reencode_to=args.reencode_to,
+                    single_pass_hash=args.single_pass_hash,
+                    path_prefix=args.path_prefix,
+                    include_empty_dirs=args.include_empty_dirs,
+                    include_hex_digest=output_format == "arrow",
+                    io_concurrency=args.io_concurrency,
                 )
 
                 write_progressive_output(
@@ -339,6 +464,9 @@ def run() -> None:
         else:
             logging.warning("Programme interrupted by user (CTRL+C).")
         sys.exit(1)
+    except TraversalAbortedError as exc:
+        logging.error("Analysis aborted: %s", exc)
+        sys.exit(1)
     except (OSError, IOError) as exc:
         logging.error("Error when writing the output file after cancellation: %s", exc)
         sys.exit(1)
@@ -0,0 +1,62 @@
+# samuraizer/utils/build_info.py
+
+"""Runtime introspection used for diagnostics and bug reports."""
+
+from __future__ import annotations
+
+import importlib.util
+import multiprocessing
+import platform
+import sys
+from importlib import metadata
+from typing import Any, Dict
+
+_FALLBACK_VERSION = "1.0.0"
+
+DEFAULT_THREAD_MULTIPLIER = 2
+
+
+def version() -> str:
+    """Return the installed Samuraizer version."""
+
+    try:
+        return metadata.version("samuraizer")
+    except metadata.PackageNotFoundError:
+        return _FALLBACK_VERSION
+
+
+def _libmagic_available() -> bool:
+    from .file_utils.mime_detection import get_magic_instance
+
+    return get_magic_instance() is not None
+
+
+def build_info() -> Dict[str, Any]:
+    """
+    Describes the running installation: version, optional features and the
+    thread defaults derived from the CPU count.
+
+    Returns:
+        Dict[str, Any]: Version, platform, feature flags and thread defaults.
+    """
+    from .file_utils.file_times import STATX_ENABLED
+
+    cpu_count = multiprocessing.cpu_count()
+    return {
+        "version": version(),
+        "python": platform.python_version(),
+        "platform": sys.platform,
+        "machine": platform.machine(),
+        "features": {
+            "libmagic": _libmagic_available(),
+            "xxhash": importlib.util.find_spec("xxhash") is not None,
+            "statx": STATX_ENABLED,
+            "ownership": importlib.util.find_spec("pwd") is not None,
+            "zstd": importlib.util.find_spec("zstandard") is not None,
+        },
+        "cpu_count": cpu_count,
+        "default_threads": cpu_count * DEFAULT_THREAD_MULTIPLIER,
+    }
+
+
+__all__ = ["DEFAULT_THREAD_MULTIPLIER", "build_info", "version"]
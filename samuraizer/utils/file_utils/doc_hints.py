@@ -0,0 +1,63 @@
+# samuraizer/utils/file_utils/doc_hints.py
+
+"""Detection of document hints such as doctypes and coding comments."""
+
+from __future__ import annotations
+
+import re
+from typing import Optional
+
+# PEP 263 coding declarations (also covers Emacs ``-*- coding: x -*-`` lines).
+_CODING_RE = re.compile(r"^[ \t\f]*#.*?coding[:=][ \t]*([-\w.]+)")
+_SHEBANG_RE = re.compile(r"^#!\s*(\S+)(?:\s+(\S+))?")
+_DOCTYPE_RE = re.compile(r"^<!doctype\s+([a-z][\w.-]*)", re.IGNORECASE)
+
+
+def _shebang_interpreter(line: str) -> Optional[str]:
+    match = _SHEBANG_RE.match(line)
+    if match is None:
+        return None
+    program, argument = match.groups()
+    name = program.rsplit("/", 1)[-1]
+    if name == "env" and argument:
+        name = argument
+    return name or None
+
+
+def detect_doc_hint(content: str) -> Optional[str]:
+    """
+    Looks for a magic comment or doctype at the start of a text preview.
+
+    Recognises HTML and XML prologues, PEP 263 coding declarations (which may
+    follow a shebang on the second line) and shebang interpreters.
+
+    Args:
+        content (str): The decoded text preview.
+
+    Returns:
+        Optional[str]: A hint such as ``"html"``, ``"xml"``,
+        ``"python-coding: utf-8"`` or ``"shebang: python3"``, or None.
+    """
+    lines = content.lstrip("\ufeff").split("\n", 2)[:2]
+    first_line = lines[0].strip()
+
+    doctype = _DOCTYPE_RE.match(first_line)
+    if doctype is not None:
+        return doctype.group(1).lower()
+    if first_line.startswith("<?xml"):
+        return "xml"
+    if first_line.lower().startswith("<html"):
+        return "html"
+
+    for line in lines:
+        coding = _CODING_RE.match(line)
+        if coding is not None:
+            return f"python-coding: {coding.group(1).lower()}"
+
+    interpreter = _shebang_interpreter(first_line)
+    if interpreter is not None:
+        return f"shebang: {interpreter}"
+    return None
+
+
+__all__ = ["detect_doc_hint"]
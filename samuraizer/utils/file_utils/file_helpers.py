@@ -3,6 +3,7 @@
 from __future__ import annotations
 
 import logging
+import stat
 from collections import Counter
 from dataclasses import dataclass
 from typing import Optional, Set
@@ -32,6 +33,7 @@ TEXTUAL_EXTENSIONS: Set[str] = {
     ".csv",
     ".dart",
     ".env",
+    ".geojson",
     ".go",
     ".gradle",
     ".h",
@@ -41,6 +43,7 @@ TEXTUAL_EXTENSIONS: Set[str] = {
     ".java",
     ".js",
     ".json",
+    ".jsonl",
     ".jsx",
     ".kt",
     ".less",
@@ -48,6 +51,7 @@ TEXTUAL_EXTENSIONS: Set[str] = {
     ".lua",
     ".m",
     ".md",
+    ".ndjson",
     ".php",
     ".pl",
     ".properties",
@@ -63,6 +67,7 @@ TEXTUAL_EXTENSIONS: Set[str] = {
     ".scss",
     ".sh",
     ".sql",
+    ".svg",
     ".swift",
     ".toml",
     ".ts",
@@ -126,6 +131,22 @@ class SampleStatistics:
     nul_ratio: float
 
 
+def special_file_kind(mode: int) -> Optional[str]:
+    """Return the kind of special file ``mode`` describes, or None for other files.
+
+    The kinds are ``fifo``, ``socket``, ``char_device`` and ``block_device``.
+    """
+    if stat.S_ISFIFO(mode):
+        return "fifo"
+    if stat.S_ISSOCK(mode):
+        return "socket"
+    if stat.S_ISCHR(mode):
+        return "char_device"
+    if stat.S_ISBLK(mode):
+        return "block_device"
+    return None
+
+
 def read_file_sample(file_path: Path, sample_size: int = HEURISTIC_SAMPLE_SIZE) -> bytes:
     with open(file_path, "rb") as fh:
         return fh.read(sample_size)
@@ -158,12 +179,49 @@ def _analyse_sample_statistics(sample: bytes) -> SampleStatistics:
     )
 
 
+def detect_wide_text_encoding(sample: bytes) -> Optional[str]:
+    """Recognise BOM-less UTF-16/UTF-32 text consisting mostly of ASCII.
+
+    Such text has zero bytes in fixed lanes (e.g. every odd byte for
+    UTF-16LE) and printable characters in the remaining lane. The checks are
+    strict so that genuinely binary data with many NUL bytes is not
+    misclassified; the sample must also decode cleanly.
+    """
+
+    for width, encodings in ((4, ("utf-32-le", "utf-32-be")), (2, ("utf-16-le", "utf-16-be"))):
+        usable = len(sample) - len(sample) % width
+        if usable < width * 8:
+            continue
+        data = sample[:usable]
+        for char_lane, encoding in zip((0, width - 1), encodings):
+            lanes = [data[offset::width] for offset in range(width)]
+            chars = lanes[char_lane]
+            zero_lanes = [lane for offset, lane in enumerate(lanes) if offset != char_lane]
+            zero_count = sum(lane.count(0) for lane in zero_lanes)
+            if zero_count < 0.95 * sum(len(lane) for lane in zero_lanes):
+                continue
+            printable = sum(1 for byte in chars if byte in _PRINTABLE_ASCII)
+            if chars.count(0) or printable < 0.90 * len(chars):
+                continue
+            try:
+                data.decode(encoding)
+            except UnicodeDecodeError:
+                continue
+            return encoding
+    return None
+
+
 def analyse_sample(sample: bytes) -> Optional[bool]:
     """Analyse the provided byte sample and return ``True`` if binary, ``False``
     if text, or ``None`` when the heuristics are inconclusive."""
 
     stats = _analyse_sample_statistics(sample)
 
+    # UTF-16/UTF-32 text without a BOM is full of NUL bytes, so it has to be
+    # recognised before the NUL check below flags it as binary.
+    if stats.nul_ratio >= 0.25 and detect_wide_text_encoding(sample) is not None:
+        return False
+
     # Strong binary signals should immediately short-circuit to avoid heavier
     # processing. NULL bytes or a large number of control characters are common
     # in compressed or compiled artefacts.
@@ -227,7 +285,9 @@ __all__ = [
     "TEXTUAL_EXTENSIONS",
     "BINARY_EXTENSIONS",
     "classify_by_extension",
+    "detect_wide_text_encoding",
     "read_file_sample",
     "analyse_sample",
     "is_binary_alternative",
+    "special_file_kind",
 ]
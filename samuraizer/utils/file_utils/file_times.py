@@ -0,0 +1,152 @@
+# samuraizer/utils/file_utils/file_times.py
+
+"""Helpers for retrieving file timestamps that ``os.stat`` does not expose."""
+
+from __future__ import annotations
+
+import ctypes
+import logging
+import os
+import sys
+from pathlib import Path
+from typing import Any, Callable, Optional
+
+logger = logging.getLogger(__name__)
+
+# ``statx`` support can be switched off (e.g. on kernels with seccomp filters
+# that kill the process instead of returning ENOSYS).
+STATX_ENABLED = sys.platform.startswith("linux") and not os.getenv("SAMURAIZER_DISABLE_STATX")
+
+_AT_FDCWD = -100
+_AT_STATX_SYNC_AS_STAT = 0x0000
+_STATX_BTIME = 0x0800
+
+
+class _StatxTimestamp(ctypes.Structure):
+    _fields_ = [
+        ("tv_sec", ctypes.c_int64),
+        ("tv_nsec", ctypes.c_uint32),
+        ("reserved", ctypes.c_int32),
+    ]
+
+
+class _Statx(ctypes.Structure):
+    _fields_ = [
+        ("stx_mask", ctypes.c_uint32),
+        ("stx_blksize", ctypes.c_uint32),
+        ("stx_attributes", ctypes.c_uint64),
+        ("stx_nlink", ctypes.c_uint32),
+        ("stx_uid", ctypes.c_uint32),
+        ("stx_gid", ctypes.c_uint32),
+        ("stx_mode", ctypes.c_uint16),
+        ("spare0", ctypes.c_uint16),
+        ("stx_ino", ctypes.c_uint64),
+        ("stx_size", ctypes.c_uint64),
+        ("stx_blocks", ctypes.c_uint64),
+        ("stx_attributes_mask", ctypes.c_uint64),
+        ("stx_atime", _StatxTimestamp),
+        ("stx_btime", _StatxTimestamp),
+        ("stx_ctime", _StatxTimestamp),
+        ("stx_mtime", _StatxTimestamp),
+        ("spare_tail", ctypes.c_uint64 * 16),
+    ]
+
+
+_statx_function: Optional[Callable[..., int]] = None
+_statx_resolved = False
+
+
+def _get_statx_function() -> Optional[Callable[..., int]]:
+    global _statx_function, _statx_resolved
+    if _statx_resolved:
+        return _statx_function
+
+    _statx_resolved = True
+    try:
+        libc = ctypes.CDLL(None, use_errno=True)
+        function = getattr(libc, "statx")
+    except (OSError, AttributeError):
+        logger.debug("statx is not available in the C library")
+        return None
+
+    function.argtypes = [
+        ctypes.c_int,
+        ctypes.c_char_p,
+        ctypes.c_int,
+        ctypes.c_uint,
+        ctypes.POINTER(_Statx),
+    ]
+    function.restype = ctypes.c_int
+    _statx_function = function
+    return _statx_function
+
+
+def _statx_birthtime_ns(file_path: Path) -> Optional[int]:
+    function = _get_statx_function()
+    if function is None:
+        return None
+
+    buffer = _Statx()
+    result = function(
+        _AT_FDCWD,
+        os.fsencode(file_path),
+        _AT_STATX_SYNC_AS_STAT,
+        _STATX_BTIME,
+        ctypes.byref(buffer),
+    )
+    if result != 0:
+        logger.debug("statx failed for %s: errno %s", file_path, ctypes.get_errno())
+        return None
+    if not buffer.stx_mask & _STATX_BTIME:
+        # The filesystem does not record birth times.
+        return None
+    return buffer.stx_btime.tv_sec * 1_000_000_000 + buffer.stx_btime.tv_nsec
+
+
+def get_birthtime_ns(file_path: Optional[Path], stat: Any) -> Optional[int]:
+    """
+    Returns the creation time of a file in nanoseconds since the epoch.
+
+    Prefers ``st_birthtime_ns`` (or ``st_birthtime``) and falls back to the
+    Linux ``statx`` system call, which reports birth times at full resolution.
+
+    Args:
+        file_path (Optional[Path]): The file, required for the ``statx`` fallback.
+        stat: The ``os.stat_result`` of the file.
+
+    Returns:
+        Optional[int]: The creation time, or None if it is unavailable.
+    """
+    birthtime_ns = getattr(stat, "st_birthtime_ns", None)
+    if isinstance(birthtime_ns, int):
+        return birthtime_ns
+    birthtime = getattr(stat, "st_birthtime", None)
+    if isinstance(birthtime, (int, float)):
+        return round(birthtime * 1_000_000_000)
+
+    if STATX_ENABLED and file_path is not None:
+        try:
+            return _statx_birthtime_ns(file_path)
+        except Exception as exc:  # pragma: no cover - defensive guard
+            logger.debug("Could not determine birth time of %s: %s", file_path, exc)
+    return None
+
+
+def get_birthtime(file_path: Optional[Path], stat: Any) -> Optional[float]:
+    """
+    Returns the creation time of a file as a POSIX timestamp.
+
+    A float view of :func:`get_birthtime_ns`, kept for callers that predate it.
+
+    Args:
+        file_path (Optional[Path]): The file, required for the ``statx`` fallback.
+        stat: The ``os.stat_result`` of the file.
+
+    Returns:
+        Optional[float]: The creation time, or None if it is unavailable.
+    """
+    birthtime_ns = get_birthtime_ns(file_path, stat)
+    return None if birthtime_ns is None else birthtime_ns / 1_000_000_000
+
+
+__all__ = ["STATX_ENABLED", "get_birthtime_ns"]
@@ -3,10 +3,12 @@
 from __future__ import annotations
 
 import logging
+import mimetypes
 import threading
+from concurrent.futures import ThreadPoolExecutor
 from functools import lru_cache
 from pathlib import Path
-from typing import Optional, Tuple
+from typing import Any, Dict, FrozenSet, Iterable, Mapping, NamedTuple, Optional, Tuple, Union
 
 import magic  # type: ignore[import-untyped]
 from colorama import Fore, Style
@@ -36,11 +38,36 @@ _TEXTUAL_MIME_PREFIXES = (
     "application/x-sh",
 )
 _TEXTUAL_MIME_TYPES = {
+    "application/geo+json",
+    "application/jsonl",
+    "application/ndjson",
     "application/x-empty",
+    "application/x-ndjson",
+    "image/svg+xml",
     "inode/x-empty",
 }
 
 
+class ClassificationRules(NamedTuple):
+    """Caller-supplied rules that adjust the classification of one traversal.
+
+    The rules are passed along with every classification instead of being
+    configured globally, so concurrent traversals do not affect each other,
+    and they are part of the classification cache key.
+    """
+
+    # Leading-byte rules that settle the classification before any heuristic;
+    # each is a ``(prefix, is_binary)`` pair and the first match wins.
+    magic_overrides: Tuple[Tuple[bytes, bool], ...] = ()
+    # Additional textual MIME prefixes/types, consulted alongside the
+    # built-in lists above.
+    textual_prefixes: Tuple[str, ...] = ()
+    textual_types: FrozenSet[str] = frozenset()
+
+
+DEFAULT_RULES = ClassificationRules()
+
+
 def get_magic_instance():
     if not hasattr(thread_local_data, "mime"):
         try:
@@ -54,13 +81,62 @@ def get_magic_instance():
     return thread_local_data.mime
 
 
-def _mime_implies_text(mime_type: str) -> bool:
-    if mime_type in _TEXTUAL_MIME_TYPES:
+def parse_textual_mime_types(
+    prefixes: Iterable[str] = (),
+    types: Iterable[str] = (),
+) -> Tuple[Tuple[str, ...], FrozenSet[str]]:
+    """Normalise additional MIME prefixes and exact MIME types treated as text.
+
+    The values extend the built-in lists when passed to
+    :class:`ClassificationRules`; blank entries are dropped and the rest are
+    lower-cased.
+    """
+
+    new_prefixes = tuple(dict.fromkeys(p.strip().lower() for p in prefixes if p.strip()))
+    new_types = frozenset(t.strip().lower() for t in types if t.strip())
+    return new_prefixes, new_types
+
+
+def parse_magic_overrides(rules: Iterable[Mapping[str, Any]]) -> Tuple[Tuple[bytes, bool], ...]:
+    """Parse magic override rules for :class:`ClassificationRules`.
+
+    Each rule is a mapping with ``hex_prefix`` (e.g. ``"89504e47"``) and
+    ``is_binary``. Rules are checked in order against the sampled bytes
+    before the content heuristics and libmagic, and the first matching
+    prefix decides the classification. This allows teaching the classifier
+    about proprietary formats.
+
+    Raises:
+        ValueError: If a prefix is empty or not valid hexadecimal.
+    """
+
+    parsed = []
+    for rule in rules:
+        hex_prefix = str(rule["hex_prefix"])
+        try:
+            prefix = bytes.fromhex(hex_prefix)
+        except ValueError as exc:
+            raise ValueError(f"Invalid magic override prefix '{hex_prefix}': {exc}") from exc
+        if not prefix:
+            raise ValueError("Magic override prefixes must not be empty")
+        parsed.append((prefix, bool(rule["is_binary"])))
+    return tuple(parsed)
+
+
+def _override_decision(sample: bytes, rules: ClassificationRules) -> Optional[bool]:
+    for prefix, binary in rules.magic_overrides:
+        if sample.startswith(prefix):
+            return binary
+    return None
+
+
+def _mime_implies_text(mime_type: str, rules: ClassificationRules = DEFAULT_RULES) -> bool:
+    if mime_type in _TEXTUAL_MIME_TYPES or mime_type in rules.textual_types:
         return True
-    return mime_type.startswith(_TEXTUAL_MIME_PREFIXES)
+    return mime_type.startswith(_TEXTUAL_MIME_PREFIXES + rules.textual_prefixes)
 
 
-def _detect_via_magic(sample: bytes, file_path: Path) -> Optional[bool]:
+def _magic_mime_type(sample: bytes, file_path: Path) -> Optional[str]:
     mime = get_magic_instance()
     if mime is None:
         return None
@@ -87,7 +163,15 @@ def _detect_via_magic(sample: bytes, file_path: Path) -> Optional[bool]:
         return None
 
     logger.debug("File: %s - MIME type: %s", file_path, mime_type)
-    if _mime_implies_text(mime_type):
+    return mime_type
+
+
+def _mime_decision(
+    mime_type: Optional[str], rules: ClassificationRules = DEFAULT_RULES
+) -> Optional[bool]:
+    if not mime_type:
+        return None
+    if _mime_implies_text(mime_type, rules):
         return False
     if mime_type == "application/octet-stream":
         return None
@@ -105,39 +189,203 @@ def _stat_key(file_path: Path) -> Optional[Tuple[str, int, int]]:
     return (str(file_path.resolve()), stat_result.st_size, int(mtime_ns))
 
 
+def _guess_mime_type(file_path: Path, binary: bool) -> str:
+    guessed, _ = mimetypes.guess_type(file_path.name)
+    if guessed:
+        return guessed
+    return "application/octet-stream" if binary else "text/plain"
+
+
+Classification = Tuple[bool, Optional[str]]
+
+
 @lru_cache(maxsize=4096)
-def _is_binary_cached(path_str: str, size: int, mtime_ns: int) -> bool:
+def _is_binary_cached(
+    path_str: str,
+    size: int,
+    mtime_ns: int,
+    content_first: bool = False,
+    include_mime: bool = False,
+    rules: ClassificationRules = DEFAULT_RULES,
+) -> Classification:
     file_path = Path(path_str)
-    return _is_binary_uncached(file_path)
+    return _is_binary_uncached(file_path, content_first, include_mime, rules)
 
 
-def _is_binary_uncached(file_path: Path) -> bool:
-    extension_decision = classify_by_extension(file_path)
-    if extension_decision is not None:
-        return extension_decision
+def _is_binary_uncached(
+    file_path: Path,
+    content_first: bool = False,
+    include_mime: bool = False,
+    rules: ClassificationRules = DEFAULT_RULES,
+) -> Classification:
+    if not content_first:
+        extension_decision = classify_by_extension(file_path)
+        if extension_decision is not None:
+            mime_type = _guess_mime_type(file_path, extension_decision) if include_mime else None
+            return extension_decision, mime_type
 
     try:
         sample = read_file_sample(file_path, HEURISTIC_SAMPLE_SIZE)
     except Exception as exc:
         logger.error("%sUnable to read sample from %s: %s%s", Fore.RED, file_path, exc, Style.RESET_ALL)
-        return is_binary_alternative(file_path)
-
-    heuristic_decision = analyse_sample(sample)
-    if heuristic_decision is not None:
-        return heuristic_decision
-
-    magic_decision = _detect_via_magic(sample, file_path)
-    if magic_decision is not None:
-        return magic_decision
-
-    return is_binary_alternative(file_path, sample=sample)
-
-
-def is_binary(file_path: Path) -> bool:
-    """Determine whether ``file_path`` should be treated as binary."""
+        decision = is_binary_alternative(file_path)
+        return decision, _guess_mime_type(file_path, decision) if include_mime else None
+
+    return _classify_sample(sample, file_path, content_first, include_mime, rules)
+
+
+def _classify_sample(
+    sample: bytes,
+    file_path: Path,
+    content_first: bool = False,
+    include_mime: bool = False,
+    rules: ClassificationRules = DEFAULT_RULES,
+) -> Classification:
+    magic_mime: Optional[str] = None
+    decision = _override_decision(sample, rules)
+    if decision is None:
+        decision = analyse_sample(sample)
+    if decision is None:
+        magic_mime = _magic_mime_type(sample, file_path)
+        decision = _mime_decision(magic_mime, rules)
+    elif include_mime:
+        # The verdict is already known; libmagic only names the type.
+        magic_mime = _magic_mime_type(sample, file_path)
+
+    if decision is None and content_first:
+        # The extension only breaks ties the content could not decide.
+        decision = classify_by_extension(file_path)
+
+    if decision is None:
+        decision = is_binary_alternative(file_path, sample=sample)
+
+    if not include_mime:
+        return decision, None
+    if magic_mime and magic_mime not in {"application/octet-stream", "application/x-empty", "inode/x-empty"}:
+        return decision, magic_mime
+    return decision, _guess_mime_type(file_path, decision)
+
+
+def is_binary(
+    file_path: Path,
+    content_first: bool = False,
+    extension_only: bool = False,
+    rules: ClassificationRules = DEFAULT_RULES,
+) -> bool:
+    """Determine whether ``file_path`` should be treated as binary.
+
+    By default well-known extensions are trusted without reading the file.
+    With ``content_first`` a sample is always analysed and the extension is
+    only consulted when the content is inconclusive, which catches files with
+    misleading extensions (e.g. a renamed archive) at the cost of a read.
+
+    With ``extension_only`` a well-known extension decides without even
+    statting the file or consulting the cache, so large lists of paths can be
+    classified by name alone; it takes precedence over ``content_first``.
+    Files with unknown extensions are still read and classified as usual.
+
+    ``rules`` adjusts the classification (see :class:`ClassificationRules`).
+    """
+
+    return classify_file(file_path, content_first, extension_only=extension_only, rules=rules)[0]
+
+
+def classify_file(
+    file_path: Path,
+    content_first: bool = False,
+    include_mime: bool = False,
+    extension_only: bool = False,
+    rules: ClassificationRules = DEFAULT_RULES,
+) -> Classification:
+    """Return the binary verdict for ``file_path`` and, optionally, its MIME type.
+
+    The MIME type is taken from libmagic on the sample that is read for
+    classification anyway; when libmagic is unavailable or unspecific (and
+    for files classified by extension alone) it is guessed from the
+    extension. Without ``include_mime`` the type is ``None``.
+    ``extension_only`` and ``rules`` are described in :func:`is_binary`.
+    """
+
+    if extension_only:
+        extension_decision = classify_by_extension(file_path)
+        if extension_decision is not None:
+            mime_type = _guess_mime_type(file_path, extension_decision) if include_mime else None
+            return extension_decision, mime_type
 
     key = _stat_key(file_path)
     if key is None:
-        return is_binary_alternative(file_path)
+        decision = is_binary_alternative(file_path)
+        return decision, _guess_mime_type(file_path, decision) if include_mime else None
 
-    return _is_binary_cached(*key)
+    return _is_binary_cached(*key, content_first, include_mime, rules)
+
+
+def _classify_for_batch(file_path: Path, content_first: bool) -> Union[bool, Dict[str, str]]:
+    try:
+        file_path.stat()
+    except OSError as exc:
+        return {"error": str(exc)}
+    return is_binary(file_path, content_first)
+
+
+def classify_many(
+    paths: Iterable[Union[str, Path]],
+    content_first: bool = False,
+    max_workers: Optional[int] = None,
+) -> Dict[str, Union[bool, Dict[str, str]]]:
+    """Classify several files at once on a thread pool.
+
+    Every path goes through :func:`is_binary`, so results land in the shared
+    classification cache and later single lookups of the same files are hits.
+
+    Args:
+        paths (Iterable[Union[str, Path]]): The files to classify.
+        content_first (bool): Passed on to :func:`is_binary`.
+        max_workers (Optional[int]): Size of the thread pool; the executor's
+            default when None.
+
+    Returns:
+        Dict[str, Union[bool, Dict[str, str]]]: Maps each path, as given, to
+        whether it is binary, or to ``{"error": message}`` when it cannot be
+        accessed.
+    """
+    names = [str(path) for path in paths]
+    if not names:
+        return {}
+    with ThreadPoolExecutor(max_workers=max_workers) as executor:
+        results = executor.map(lambda name: _classify_for_batch(Path(name), content_first), names)
+        return dict(zip(names, results))
+
+
+def is_binary_bytes(
+    data: bytes,
+    name: str,
+    content_first: bool = False,
+    rules: ClassificationRules = DEFAULT_RULES,
+) -> bool:
+    """Classify an in-memory buffer; ``name`` enables the extension fast path."""
+
+    name_path = Path(name)
+    if not content_first:
+        extension_decision = classify_by_extension(name_path)
+        if extension_decision is not None:
+            return extension_decision
+    return _classify_sample(data[:HEURISTIC_SAMPLE_SIZE], name_path, content_first, rules=rules)[0]
+
+
+def mime_cache_stats() -> Dict[str, Optional[int]]:
+    """Return hit/miss counters and occupancy of the classification cache."""
+
+    info = _is_binary_cached.cache_info()
+    return {
+        "hits": info.hits,
+        "misses": info.misses,
+        "len": info.currsize,
+        "capacity": info.maxsize,
+    }
+
+
+def clear_mime_cache() -> None:
+    """Forget all memoised file classifications."""
+
+    _is_binary_cached.cache_clear()
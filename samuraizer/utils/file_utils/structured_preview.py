@@ -0,0 +1,70 @@
+# samuraizer/utils/file_utils/structured_preview.py
+
+"""Parsing of configuration-style files into JSON-compatible values."""
+
+from __future__ import annotations
+
+import json
+from datetime import date, datetime, time
+from pathlib import Path
+from typing import Any, Callable, Dict
+
+import yaml
+
+from ...config.compat import tomllib
+
+
+def _parse_yaml(content: str) -> Any:
+    return yaml.safe_load(content)
+
+
+_PARSERS: Dict[str, Callable[[str], Any]] = {
+    ".json": json.loads,
+    ".toml": tomllib.loads,
+    ".yaml": _parse_yaml,
+    ".yml": _parse_yaml,
+}
+
+STRUCTURED_EXTENSIONS = frozenset(_PARSERS)
+
+
+def _normalize(value: Any) -> Any:
+    """Convert parser output into values every output format can serialise."""
+
+    if isinstance(value, dict):
+        return {str(key): _normalize(item) for key, item in value.items()}
+    if isinstance(value, (list, tuple, set)):
+        return [_normalize(item) for item in value]
+    if isinstance(value, (datetime, date, time)):
+        return value.isoformat()
+    if isinstance(value, bytes):
+        return value.decode("utf-8", errors="replace")
+    return value
+
+
+def parse_structured_content(file_path: Path, content: str) -> Any:
+    """
+    Parses JSON, TOML or YAML content based on the file extension.
+
+    Args:
+        file_path (Path): The file the content was read from.
+        content (str): The decoded text preview.
+
+    Returns:
+        Any: The parsed document as JSON-compatible values.
+
+    Raises:
+        ValueError: If the extension is unsupported or the content is invalid.
+    """
+    parser = _PARSERS.get(file_path.suffix.lower())
+    if parser is None:
+        raise ValueError(f"No structured parser for {file_path.suffix or 'files without extension'}")
+
+    try:
+        return _normalize(parser(content))
+    except (ValueError, yaml.YAMLError) as exc:
+        # json.JSONDecodeError and TOMLDecodeError are ValueError subclasses.
+        raise ValueError(str(exc)) from exc
+
+
+__all__ = ["STRUCTURED_EXTENSIONS", "parse_structured_content"]
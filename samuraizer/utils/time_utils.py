@@ -3,17 +3,21 @@ from datetime import timezone, tzinfo
 import logging
 from typing import Any, Optional
 
-def format_timestamp(timestamp: Any, target_tz: Optional[tzinfo] = None) -> str:
+def format_timestamp(timestamp: Any, target_tz: Optional[tzinfo] = None) -> Optional[str]:
     """
     Formats a timestamp in ISO 8601 format.
     
     Args:
-        timestamp: Unix timestamp to format
+        timestamp: Unix timestamp or an already formatted ISO string
         target_tz: Optional target timezone. If None, uses UTC.
     
     Returns:
-        Formatted timestamp string in ISO format with timezone information
+        Formatted timestamp string in ISO format with timezone information,
+        or None if the timestamp is unavailable (e.g. no creation time was
+        recorded) so that it is never mistaken for the epoch
     """
+    if isinstance(timestamp, str):
+        return timestamp or None
     if isinstance(timestamp, (int, float)):
         try:
             # First convert to UTC datetime
@@ -28,8 +32,8 @@ def format_timestamp(timestamp: Any, target_tz: Optional[tzinfo] = None) -> str:
             return utc_dt.isoformat()
         except (OSError, OverflowError, ValueError):
             logging.warning(f"Invalid timestamp: {timestamp}")
-            return ""
-    return ""
+            return None
+    return None
 
 def get_system_timezone() -> tzinfo:
     """
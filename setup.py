@@ -53,7 +53,15 @@ extras_require = {
         'isort>=5.12.0',
         'mypy>=1.4.1',
         'flake8>=6.1.0',
-    ]
+    ],
+    # Preview of zstd-compressed files (--decompress)
+    'zstd': [
+        'zstandard>=0.22.0',
+    ],
+    # Columnar output for pandas/polars (--format arrow)
+    'arrow': [
+        'pyarrow>=12.0.0',
+    ],
 }
 
 setup(
@@ -0,0 +1,93 @@
+from __future__ import annotations
+
+import sys
+from pathlib import Path
+
+import pytest
+
+ROOT = str(Path(__file__).resolve().parents[1])
+if ROOT not in sys.path:  # pragma: no cover - defensive path setup
+    sys.path.insert(0, ROOT)
+
+from samuraizer.backend.output.formatters.arrow import arrow_output
+
+
+def test_entry_row_keeps_only_the_columnar_fields():
+    text = {
+        "type": "text",
+        "content": "x\n",
+        "encoding": "utf-8",
+        "size": 2,
+        "modified_ns": 1_700_000_000_000_000_000,
+        "file_hash": "abc",
+        "mime_type": "text/plain",
+    }
+    binary = {"type": "binary", "encoding": "base64", "size": 4}
+
+    assert arrow_output.entry_row("a.txt", text) == {
+        "path": "a.txt",
+        "size": 2,
+        "mtime": 1_700_000_000_000_000_000,
+        "hash": "abc",
+        "is_binary": False,
+        "mime": "text/plain",
+        "encoding": "utf-8",
+    }
+    row = arrow_output.entry_row("b.bin", binary)
+    assert row["is_binary"] is True
+    assert row["encoding"] is None
+    assert arrow_output.entry_row("c", {"type": "excluded"})["is_binary"] is None
+
+
+def test_output_to_arrow_stream_writes_batches(tmp_path: Path):
+    pyarrow = pytest.importorskip("pyarrow")
+    output_file = tmp_path / "out.arrow"
+    records = [
+        {"path": f"dir/{index}.txt", "info": {"type": "text", "encoding": "utf-8", "size": index}}
+        for index in range(5)
+    ]
+
+    arrow_output.output_to_arrow_stream(
+        iter(records + [{"summary": {}}]), str(output_file), {"arrow_batch_size": 2}
+    )
+
+    with pyarrow.ipc.open_file(str(output_file)) as reader:
+        assert reader.num_record_batches == 3
+        table = reader.read_all()
+    assert table.column("path").to_pylist() == [f"dir/{index}.txt" for index in range(5)]
+    assert table.column("size").to_pylist() == list(range(5))
+
+
+def test_hash_column_holds_the_hex_digest_from_traversal(monkeypatch, tmp_path: Path):
+    from samuraizer.backend.analysis import file_processor
+    from samuraizer.backend.analysis.hash_service import HashService
+    from samuraizer.backend.analysis.traversal.traversal_processor import generate_directory_chunks
+
+    monkeypatch.setattr(file_processor, "is_cache_disabled", lambda: True)
+    (tmp_path / "a.txt").write_text("alpha\n", encoding="utf-8")
+    expected = HashService.compute_bytes_hash(b"alpha\n")
+
+    for hash_encoding in (None, "base64"):
+        chunks = generate_directory_chunks(
+            root_dir=tmp_path,
+            max_file_size=1024 * 1024,
+            include_binary=False,
+            excluded_folders=set(),
+            excluded_files=set(),
+            follow_symlinks=False,
+            image_extensions=set(),
+            exclude_patterns=[],
+            threads=1,
+            encoding=None,
+            hashing_enabled=True,
+            progress_callback=None,
+            cancellation_token=None,
+            hash_encoding=hash_encoding,
+            include_hex_digest=True,
+            chunk_size=10,
+            max_pending_tasks=4,
+        )
+        entries = [entry for payload in chunks for entry in payload.get("entries", [])]
+
+        rows = [arrow_output.entry_row(entry["relative_path"], entry["info"]) for entry in entries]
+        assert [row["hash"] for row in rows] == [expected]
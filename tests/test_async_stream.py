@@ -85,3 +85,179 @@ def test_env_override_applies(monkeypatch):
 
     assert results == [0, 1, 2]
     assert produced == results
+
+
+def test_traversal_poller_does_not_block_event_loop(monkeypatch):
+    release = []
+
+    def fake_stream(*args, **kwargs):
+        while not release:
+            time.sleep(0.01)
+        for index in range(3):
+            yield {"idx": index}
+
+    monkeypatch.setattr(async_traversal, "get_directory_structure_stream", fake_stream)
+
+    async def consume() -> tuple[list[int], int]:
+        items = []
+        pending_polls = 0
+        with async_traversal.TraversalPoller() as poller:
+            while True:
+                item = poller.poll()
+                if item is poller.DONE:
+                    break
+                if item is poller.PENDING:
+                    pending_polls += 1
+                    release.append(True)
+                    await asyncio.sleep(0.01)
+                    continue
+                items.append(item["idx"])
+        return items, pending_polls
+
+    items, pending_polls = asyncio.run(consume())
+
+    assert items == [0, 1, 2]
+    assert pending_polls >= 1
+
+
+def test_traversal_poller_estimates_remaining_files(monkeypatch):
+    def fake_stream(*args, progress=None, **kwargs):
+        assert progress.approximate_remaining() is None
+        progress.included = 3
+        for index in range(3):
+            progress.processed = index + 1
+            yield {"idx": index}
+
+    monkeypatch.setattr(async_traversal, "get_directory_structure_stream", fake_stream)
+
+    remaining = []
+    with async_traversal.TraversalPoller() as poller:
+        while True:
+            item = poller.poll()
+            if item is poller.DONE:
+                break
+            if item is poller.PENDING:
+                time.sleep(0.01)
+                continue
+            remaining.append(poller.approximate_remaining())
+
+    assert all(count is not None and 0 <= count <= 2 for count in remaining)
+    assert poller.approximate_remaining() == 0
+
+
+def test_progress_tracks_processed_and_included_files(tmp_path: Path):
+    from samuraizer.backend.analysis.traversal.traversal_core import TraversalProgress
+    from samuraizer.backend.analysis.traversal.traversal_processor import get_directory_structure
+
+    for name in ("a.txt", "b.txt", "c.txt"):
+        (tmp_path / name).write_text(name, encoding="utf-8")
+    progress = TraversalProgress()
+    assert progress.approximate_remaining() is None
+
+    get_directory_structure(
+        root_dir=tmp_path,
+        max_file_size=1024,
+        include_binary=True,
+        excluded_folders=set(),
+        excluded_files=set(),
+        follow_symlinks=False,
+        image_extensions=set(),
+        exclude_patterns=[],
+        threads=1,
+        hashing_enabled=False,
+        progress=progress,
+    )
+
+    assert progress.included == 3
+    assert progress.processed == 3
+    assert progress.approximate_remaining() == 0
+
+
+def test_progress_reports_the_current_file(tmp_path: Path):
+    from samuraizer.backend.analysis.traversal.traversal_core import TraversalProgress
+    from samuraizer.backend.analysis.traversal.traversal_processor import get_directory_structure
+
+    (tmp_path / "src").mkdir()
+    for name in ("a.txt", "b.txt", "c.txt"):
+        (tmp_path / "src" / name).write_text(name, encoding="utf-8")
+    progress = TraversalProgress()
+    seen = []
+
+    get_directory_structure(
+        root_dir=tmp_path,
+        max_file_size=1024,
+        include_binary=True,
+        excluded_folders=set(),
+        excluded_files=set(),
+        follow_symlinks=False,
+        image_extensions=set(),
+        exclude_patterns=[],
+        threads=1,
+        hashing_enabled=False,
+        sort_by="name",
+        progress=progress,
+        progress_callback=lambda _count: seen.append(progress.current),
+    )
+
+    assert seen == [(0, "src/a.txt"), (1, "src/b.txt"), (2, "src/c.txt")]
+
+
+def test_traversal_poller_keeps_the_summary(monkeypatch):
+    def fake_stream(*args, **kwargs):
+        yield {"filename": "a.txt"}
+        yield {"summary": {"included_files": 1}}
+
+    monkeypatch.setattr(async_traversal, "get_directory_structure_stream", fake_stream)
+
+    with async_traversal.TraversalPoller() as poller:
+        assert poller.summary() is None
+        while True:
+            item = poller.poll()
+            if item is poller.DONE:
+                break
+            if item is poller.PENDING:
+                time.sleep(0.01)
+
+    assert poller.summary() == {"included_files": 1}
+
+
+def test_traversal_poller_close_cancels_the_traversal(monkeypatch):
+    tokens = []
+
+    def fake_stream(*args, cancellation_token=None, **kwargs):
+        tokens.append(cancellation_token)
+        # A traversal busy with a slow file yields nothing until it is cancelled.
+        cancellation_token.wait(5)
+        yield from ()
+
+    monkeypatch.setattr(async_traversal, "get_directory_structure_stream", fake_stream)
+
+    poller = async_traversal.TraversalPoller()
+    while not tokens:
+        time.sleep(0.01)
+    started = time.monotonic()
+    poller.close()
+
+    assert tokens[0].is_cancellation_requested()
+    assert not poller._thread.is_alive()
+    assert time.monotonic() - started < 2
+
+
+def test_traversal_poller_keeps_a_caller_cancellation_token(monkeypatch):
+    from samuraizer.backend.services.event_service.cancellation import CancellationTokenSource
+
+    tokens = []
+
+    def fake_stream(*args, cancellation_token=None, **kwargs):
+        tokens.append(cancellation_token)
+        yield {"idx": 0}
+
+    monkeypatch.setattr(async_traversal, "get_directory_structure_stream", fake_stream)
+    source = CancellationTokenSource()
+
+    with async_traversal.TraversalPoller(cancellation_token=source.token):
+        while not tokens:
+            time.sleep(0.01)
+
+    assert tokens == [source.token]
+    assert not source.is_cancelled()
@@ -0,0 +1,13 @@
+import multiprocessing
+
+from samuraizer.utils.build_info import build_info, version
+
+
+def test_build_info_reports_version_features_and_threads():
+    info = build_info()
+
+    assert info["version"] == version()
+    assert set(info["features"]) >= {"libmagic", "xxhash", "statx", "ownership"}
+    assert all(isinstance(flag, bool) for flag in info["features"].values())
+    assert info["cpu_count"] == multiprocessing.cpu_count()
+    assert info["default_threads"] >= info["cpu_count"]
@@ -0,0 +1,60 @@
+from __future__ import annotations
+
+import sys
+import threading
+from pathlib import Path
+from types import SimpleNamespace
+
+ROOT = str(Path(__file__).resolve().parents[1])
+if ROOT not in sys.path:  # pragma: no cover - defensive path setup
+    sys.path.insert(0, ROOT)
+
+
+class _FakeMagic:
+    def __init__(self, mime: bool = True) -> None:  # pragma: no cover - simple stub
+        self.mime = mime
+
+    def from_buffer(self, _: bytes) -> str:  # pragma: no cover - simple stub
+        return "text/plain"
+
+
+sys.modules.setdefault("magic", SimpleNamespace(Magic=_FakeMagic))
+
+from samuraizer.backend.analysis import hash_service
+from samuraizer.backend.analysis.cache_reset import reset_caches
+from samuraizer.backend.services.pattern_service import compile_regex
+from samuraizer.utils.file_utils import mime_detection
+
+
+def test_reset_caches_clears_every_cache(tmp_path: Path):
+    file_path = tmp_path / "a.txt"
+    file_path.write_text("hello\n", encoding="utf-8")
+    mime_detection.is_binary(file_path)
+    hash_service._DIGEST_CACHE.put((str(file_path), 6, 0), "digest")
+    compile_regex(r"^a+$")
+
+    reset_caches()
+
+    assert mime_detection.mime_cache_stats()["len"] == 0
+    assert hash_service._DIGEST_CACHE.get((str(file_path), 6, 0)) is None
+    assert compile_regex.cache_info().currsize == 0
+
+
+def test_reset_caches_is_safe_to_call_concurrently():
+    errors = []
+
+    def worker() -> None:
+        try:
+            for index in range(200):
+                compile_regex(f"^{index}$")
+                reset_caches()
+        except Exception as exc:  # pragma: no cover - only reached on failure
+            errors.append(exc)
+
+    threads = [threading.Thread(target=worker) for _ in range(4)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+
+    assert errors == []
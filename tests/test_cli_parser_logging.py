@@ -4,6 +4,8 @@ import sys
 from pathlib import Path
 from types import ModuleType
 
+import pytest
+
 PROJECT_ROOT = Path(__file__).resolve().parents[1]
 if str(PROJECT_ROOT) not in sys.path:
     sys.path.insert(0, str(PROJECT_ROOT))
@@ -26,3 +28,14 @@ def test_cli_parser_import_does_not_clear_existing_root_handlers():
         assert after_handlers == before_handlers
     finally:
         root_logger.removeHandler(sentinel_handler)
+
+
+def test_count_options_reject_values_below_one():
+    parser = reload_module("samuraizer.cli.parser")
+    args = parser.parse_arguments(["repo", "-o", "out.json", "--max-pending-tasks", "3"])
+    assert args.max_pending_tasks == 3
+
+    for option in ("--max-pending-tasks", "--threads", "--max-open-files"):
+        for value in ("0", "-1"):
+            with pytest.raises(SystemExit):
+                parser.parse_arguments(["repo", "-o", "out.json", option, value])
@@ -91,3 +91,20 @@ def test_batch_updates_reduce_notifications(
         }
     )
     assert events == 0
+
+
+def test_timezone_offset_minutes(unified_manager: UnifiedConfigManager) -> None:
+    from samuraizer.config.timezone_service import TimezoneService
+
+    service = TimezoneService(unified_manager)
+    service.set_timezone_offset(330)
+
+    target = service.get_timezone()
+    assert str(target) == "+05:30"
+    assert target.utcoffset(None).total_seconds() == 330 * 60
+
+    service.use_utc(True)
+    assert str(service.get_timezone()) == "UTC"
+
+    with pytest.raises(ValueError):
+        service.set_timezone_offset(24 * 60)
@@ -0,0 +1,47 @@
+from pathlib import Path
+
+from samuraizer.backend.analysis.traversal.traversal_core import traverse_and_collect
+from samuraizer.backend.services.dockerignore_service import DockerIgnore
+
+
+def test_negation_is_evaluated_top_to_bottom():
+    rules = DockerIgnore.parse(["*.md", "!README.md", "README*.md"])
+
+    assert rules.matches("CHANGES.md")
+    # The last rule wins, so README.md is excluded again.
+    assert rules.matches("README.md")
+
+    rules = DockerIgnore.parse(["*.md", "!README.md"])
+    assert not rules.matches("README.md")
+    assert not rules.matches("docs/notes.md")
+
+
+def test_patterns_are_anchored_at_the_context_root():
+    rules = DockerIgnore.parse(["# comment", "/build", "temp?", "**/*.log"])
+
+    assert rules.matches("build")
+    assert rules.matches("build/out.bin")
+    assert not rules.matches("src/build")
+    assert rules.matches("temp1")
+    assert not rules.matches("src/temp1")
+    assert rules.matches("debug.log")
+    assert rules.matches("a/b/debug.log")
+
+
+def test_dockerignore_applies_during_traversal(tmp_path: Path):
+    (tmp_path / "app.py").write_text("print()\n", encoding="utf-8")
+    (tmp_path / "node_modules").mkdir()
+    (tmp_path / "node_modules" / "dep.js").write_text("x\n", encoding="utf-8")
+    (tmp_path / "docs").mkdir()
+    (tmp_path / "docs" / "keep.md").write_text("k\n", encoding="utf-8")
+    (tmp_path / "docs" / "drop.md").write_text("d\n", encoding="utf-8")
+    dockerignore = tmp_path / ".dockerignore"
+    dockerignore.write_text("node_modules\ndocs\n!docs/keep.md\n.dockerignore\n", encoding="utf-8")
+
+    iterator, counters = traverse_and_collect(
+        tmp_path, set(), set(), [], False, dockerignore_path=dockerignore
+    )
+    files = sorted(path.relative_to(tmp_path).as_posix() for path in iterator)
+
+    assert files == ["app.py", "docs/keep.md"]
+    assert counters.excluded_reasons["dockerignore"] == 3
@@ -0,0 +1,789 @@
+from __future__ import annotations
+
+import base64
+import io
+import os
+import sys
+from contextlib import contextmanager
+from pathlib import Path
+from types import SimpleNamespace
+
+import pytest
+
+ROOT = str(Path(__file__).resolve().parents[1])
+if ROOT not in sys.path:  # pragma: no cover - defensive path setup
+    sys.path.insert(0, ROOT)
+
+
+class _FakeMagic:
+    def __init__(self, mime: bool = True) -> None:  # pragma: no cover - simple stub
+        self.mime = mime
+
+    def from_buffer(self, _: bytes) -> str:  # pragma: no cover - simple stub
+        return "text/plain"
+
+
+sys.modules.setdefault("magic", SimpleNamespace(Magic=_FakeMagic))
+
+from samuraizer.backend.analysis import file_processor
+from samuraizer.backend.analysis.hash_service import HashService
+
+
+@pytest.fixture
+def fake_cache(monkeypatch: pytest.MonkeyPatch):
+    store: dict = {}
+
+    @contextmanager
+    def connection_context():
+        yield object()
+
+    def get_cached_entry(_conn, path):
+        return store.get(path)
+
+    def set_cached_entry(
+        _conn, path, file_hash, file_info, size, mtime, synchronous=False, options=None
+    ):
+        store[path] = {
+            "file_hash": file_hash,
+            "file_info": file_info,
+            "size": size,
+            "mtime": mtime,
+            "options": options,
+        }
+
+    monkeypatch.setattr(file_processor, "is_cache_disabled", lambda: False)
+    monkeypatch.setattr(file_processor, "get_connection_context", connection_context)
+    monkeypatch.setattr(file_processor, "get_cached_entry", get_cached_entry)
+    monkeypatch.setattr(file_processor, "set_cached_entry", set_cached_entry)
+    return store
+
+
+def _process(file_path: Path, **kwargs):
+    return file_processor.process_file(file_path, 1024 * 1024, True, set(), **kwargs)
+
+
+def test_verify_hash_ignores_stale_mtime_when_content_matches(fake_cache, tmp_path: Path):
+    file_path = tmp_path / "config.txt"
+    file_path.write_text("value\n", encoding="utf-8")
+    key = str(file_path.resolve())
+    _process(file_path)
+    fake_cache[key].update({"file_info": {"type": "text", "content": "cached"}, "mtime": 0.0})
+
+    _, info = _process(file_path, verify_hash=True)
+
+    assert info["content"] == "cached"
+    assert fake_cache[key]["mtime"] == file_path.stat().st_mtime
+
+
+def test_verify_hash_treats_hash_mismatch_as_miss(fake_cache, tmp_path: Path):
+    file_path = tmp_path / "config.txt"
+    file_path.write_text("value\n", encoding="utf-8")
+    _process(file_path)
+    fake_cache[str(file_path.resolve())].update(
+        {"file_hash": "0000000000000000", "file_info": {"type": "text", "content": "cached"}}
+    )
+
+    _, info = _process(file_path, verify_hash=True)
+
+    assert info["content"] == "value\n"
+
+
+def test_binary_preview_bytes_caps_binary_content_only(tmp_path: Path):
+    binary_path = tmp_path / "blob.bin"
+    binary_path.write_bytes(b"\x00\x01" * 512)
+    text_path = tmp_path / "notes.txt"
+    text_path.write_text("a" * 1024, encoding="utf-8")
+
+    _, binary_info = _process(binary_path, hashing_enabled=False, binary_preview_bytes=64)
+    _, text_info = _process(text_path, hashing_enabled=False, binary_preview_bytes=64)
+
+    assert binary_info["type"] == "binary"
+    assert binary_info["preview_bytes"] == 64
+    assert binary_info["truncated"] is True
+    assert text_info["type"] == "text"
+    assert len(text_info["content"]) == 1024
+
+
+def test_binary_content_disabled_reports_metadata_only(monkeypatch, tmp_path: Path):
+    binary_path = tmp_path / "archive.zip"
+    binary_path.write_bytes(b"PK\x03\x04" + b"\x00" * 128)
+
+    def fail_open(*_args, **_kwargs):
+        raise AssertionError("binary file must not be opened")
+
+    monkeypatch.setattr(file_processor, "open", fail_open, raising=False)
+
+    _, info = _process(binary_path, hashing_enabled=False, binary_content=False)
+
+    assert info["type"] == "binary"
+    assert info["content_omitted"] is True
+    assert "content" not in info
+    assert info["size"] == 132
+
+
+@pytest.mark.skipif(file_processor.pwd is None, reason="ownership is only reported on Unix")
+def test_metadata_includes_ownership(tmp_path: Path):
+    file_path = tmp_path / "owned.txt"
+    file_path.write_text("owned\n", encoding="utf-8")
+    stat = file_path.stat()
+
+    _, info = _process(file_path, hashing_enabled=False)
+
+    assert info["uid"] == stat.st_uid
+    assert info["gid"] == stat.st_gid
+    assert info["owner"] == file_processor.pwd.getpwuid(stat.st_uid).pw_name
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="symbolic permissions are Unix only")
+def test_metadata_includes_symbolic_permissions(tmp_path: Path):
+    file_path = tmp_path / "script.sh"
+    file_path.write_text("#!/bin/sh\n", encoding="utf-8")
+    file_path.chmod(0o4754)
+
+    _, info = _process(file_path, hashing_enabled=False)
+
+    assert info["permissions_symbolic"] == "-rwsr-xr--"
+    assert info["permissions"] == oct(file_path.stat().st_mode)
+
+
+def test_hash_encoding_reports_file_hash_without_changing_cache(fake_cache, tmp_path: Path):
+    file_path = tmp_path / "config.txt"
+    file_path.write_text("value\n", encoding="utf-8")
+    hex_digest = HashService.compute_file_hash(file_path)
+
+    _, info = _process(file_path, hash_encoding="dec")
+    _, cached = _process(file_path, hash_encoding="hex")
+
+    assert info["file_hash"] == str(int(hex_digest, 16))
+    assert cached["file_hash"] == hex_digest
+    stored = fake_cache[str(file_path.resolve())]
+    assert stored["file_hash"] == hex_digest
+    assert "file_hash" not in stored["file_info"]
+
+
+def test_quick_hash_is_opt_in(tmp_path: Path):
+    file_path = tmp_path / "data.txt"
+    file_path.write_text("payload\n", encoding="utf-8")
+
+    _, plain = _process(file_path, hashing_enabled=False)
+    _, info = _process(file_path, hashing_enabled=False, quick_hash=True)
+
+    assert "quick_hash" not in plain
+    assert info["quick_hash"] == HashService.compute_quick_hash(file_path)
+
+
+def test_read_text_preview_fd_handles_pipes():
+    read_fd, write_fd = os.pipe()
+    try:
+        with os.fdopen(write_fd, "wb") as writer:
+            writer.write("héllo from a pipe\n".encode("utf-8") * 4)
+
+        info = file_processor.read_text_preview_fd(read_fd, 40, encoding="utf-8")
+    finally:
+        os.close(read_fd)
+
+    assert info["type"] == "text"
+    assert info["preview_bytes"] == 40
+    assert info["content"].startswith("héllo from a pipe\nhéllo")
+    assert info["truncated"] is True
+
+
+def test_read_binary_preview_fd_reads_whole_stream(tmp_path: Path):
+    file_path = tmp_path / "blob.bin"
+    file_path.write_bytes(b"\x00\x01\x02\x03")
+
+    with file_path.open("rb") as handle:
+        info = file_processor.read_binary_preview_fd(handle.fileno())
+
+    assert base64.b64decode(info["content"]) == b"\x00\x01\x02\x03"
+    assert "truncated" not in info
+
+
+def test_fd_previews_start_at_the_current_offset_and_leave_the_rest(tmp_path: Path):
+    file_path = tmp_path / "log.txt"
+    file_path.write_bytes(b"header\nbody line\ntail\n")
+
+    with file_path.open("rb") as handle:
+        fd = handle.fileno()
+        os.lseek(fd, 7, os.SEEK_SET)
+        text = file_processor.read_text_preview_fd(fd, 10, encoding="utf-8")
+        assert os.lseek(fd, 0, os.SEEK_CUR) == 17
+        binary = file_processor.read_binary_preview_fd(fd, preview_bytes=2)
+        assert os.read(fd, 100) == b"il\n"
+
+    assert text["content"] == "body line\n"
+    assert text["truncated"] is True
+    assert base64.b64decode(binary["content"]) == b"ta"
+
+
+def test_fd_preview_without_truncation_probe_leaves_the_pipe_intact():
+    read_fd, write_fd = os.pipe()
+    try:
+        with os.fdopen(write_fd, "wb") as writer:
+            writer.write(b"0123456789")
+
+        info = file_processor.read_binary_preview_fd(read_fd, preview_bytes=4, probe_truncation=False)
+        rest = os.read(read_fd, 100)
+    finally:
+        os.close(read_fd)
+
+    assert base64.b64decode(info["content"]) == b"0123"
+    assert info["truncated"] is True
+    assert rest == b"456789"
+
+
+@pytest.mark.parametrize(
+    ("content", "expected"),
+    [
+        (b"one\ntwo\n", "lf"),
+        (b"one\r\ntwo\r\n", "crlf"),
+        (b"one\rtwo\r", "cr"),
+        (b"one\r\ntwo\n", "mixed"),
+        (b"single line", "none"),
+    ],
+)
+def test_text_preview_reports_line_ending(tmp_path: Path, content: bytes, expected: str):
+    file_path = tmp_path / "lines.txt"
+    file_path.write_bytes(content)
+
+    _, info = _process(file_path, hashing_enabled=False)
+
+    assert info["line_ending"] == expected
+
+
+def test_text_preview_reports_final_newline(monkeypatch, tmp_path: Path):
+    with_newline = tmp_path / "with.txt"
+    with_newline.write_text("done\n", encoding="utf-8")
+    without_newline = tmp_path / "without.txt"
+    without_newline.write_text("done", encoding="utf-8")
+    truncated = tmp_path / "long.txt"
+    truncated.write_text("line\n" * 100, encoding="utf-8")
+
+    _, with_info = _process(with_newline, hashing_enabled=False)
+    _, without_info = _process(without_newline, hashing_enabled=False)
+    monkeypatch.setattr(file_processor, "_MAX_TEXT_CONTENT_BYTES", 64)
+    _, truncated_info = _process(truncated, hashing_enabled=False)
+
+    assert with_info["final_newline"] is True
+    assert without_info["final_newline"] is False
+    assert truncated_info["truncated"] is True
+    assert truncated_info["final_newline"] is None
+
+
+def test_jsonl_previews_as_text(tmp_path: Path):
+    file_path = tmp_path / "events.jsonl"
+    file_path.write_text('{"name": "café"}\n{"name": "naïve"}\n', encoding="utf-8")
+
+    _, info = _process(file_path, hashing_enabled=False)
+
+    assert info["type"] == "text"
+    assert info["encoding"].replace("_", "-") == "utf-8"
+    assert "café" in info["content"]
+
+
+def test_parse_structured_adds_parsed_preview(tmp_path: Path):
+    toml_path = tmp_path / "settings.toml"
+    toml_path.write_text('name = "demo"\nreleased = 2024-01-02\n[tool]\nlevel = 3\n', encoding="utf-8")
+    yaml_path = tmp_path / "broken.yaml"
+    yaml_path.write_text("key: [unclosed\n", encoding="utf-8")
+
+    _, toml_info = _process(toml_path, hashing_enabled=False, parse_structured=True)
+    _, yaml_info = _process(yaml_path, hashing_enabled=False, parse_structured=True)
+    _, plain_info = _process(toml_path, hashing_enabled=False)
+
+    assert toml_info["parsed"] == {"name": "demo", "released": "2024-01-02", "tool": {"level": 3}}
+    assert "parsed" not in yaml_info
+    assert yaml_info["parse_error"]
+    assert "parsed" not in plain_info
+
+
+def test_process_bytes_matches_file_processing(tmp_path: Path):
+    text = "name,value\nalpha,1\n".encode("utf-8")
+    file_path = tmp_path / "table.csv"
+    file_path.write_bytes(text)
+
+    _, from_file = _process(file_path, hashing_enabled=False)
+    name, from_memory = file_processor.process_bytes(text, "archive/table.csv")
+    _, binary = file_processor.process_bytes(b"\x00\x01\x02", "blob.bin", hashing_enabled=False)
+
+    assert name == "table.csv"
+    assert from_memory["type"] == "text"
+    assert from_memory["content"] == from_file["content"]
+    assert from_memory["size"] == len(text)
+    assert from_memory["modified"] is None
+    assert from_memory["file_hash"] == HashService.compute_file_hash(file_path)
+    assert binary["type"] == "binary"
+    assert base64.b64decode(binary["content"]) == b"\x00\x01\x02"
+
+
+def test_encoding_sample_bytes_limits_detection_sample(monkeypatch, tmp_path: Path):
+    file_path = tmp_path / "large.txt"
+    file_path.write_text("x" * 4096, encoding="utf-8")
+    small_path = tmp_path / "small.txt"
+    small_path.write_text("tiny", encoding="utf-8")
+    sample_sizes = []
+    original = file_processor.charset_normalizer.from_bytes
+
+    def recording_from_bytes(sample, *args, **kwargs):
+        sample_sizes.append(len(sample))
+        return original(sample, *args, **kwargs)
+
+    monkeypatch.setattr(file_processor.charset_normalizer, "from_bytes", recording_from_bytes)
+
+    _, info = _process(file_path, hashing_enabled=False, encoding_sample_bytes=100)
+    _process(small_path, hashing_enabled=False, encoding_sample_bytes=100)
+
+    assert sample_sizes == [100, 4]
+    assert len(info["content"]) == 4096
+
+
+def test_strict_decode_reports_malformed_text(tmp_path: Path):
+    file_path = tmp_path / "broken.txt"
+    file_path.write_bytes(b"valid start \xff\xfe invalid bytes\n")
+
+    _, lossy = _process(file_path, hashing_enabled=False, encoding="utf-8")
+    _, strict = _process(file_path, hashing_enabled=False, encoding="utf-8", strict_decode=True)
+
+    assert lossy["type"] == "text"
+    assert "�" in lossy["content"]
+    assert strict["type"] == "error"
+    assert strict["reason"] == "decode_error"
+
+
+def test_text_preview_reports_decode_errors(tmp_path: Path):
+    broken = tmp_path / "broken.txt"
+    broken.write_bytes(b"valid start \xff\xfe invalid bytes\n")
+    clean = tmp_path / "clean.txt"
+    clean.write_text("replacement char � is legitimate here\n", encoding="utf-8")
+
+    _, broken_info = _process(broken, hashing_enabled=False, encoding="utf-8")
+    _, clean_info = _process(clean, hashing_enabled=False, encoding="utf-8")
+
+    assert broken_info["had_decode_errors"] is True
+    assert clean_info["had_decode_errors"] is False
+
+
+def test_preview_cut_inside_a_multibyte_character_is_not_a_decode_error():
+    data = ("a" * 9 + "é").encode("utf-8")
+
+    content, _, bytes_read, had_errors = file_processor._read_text_stream(
+        io.BytesIO(data), 10, "utf-8", "test"
+    )
+
+    assert (content, bytes_read, had_errors) == ("a" * 9, 10, False)
+
+
+def test_invalid_tail_ending_exactly_at_the_read_limit_is_a_decode_error(
+    monkeypatch, tmp_path: Path
+):
+    data = b"a" * 9 + b"\xc3"
+
+    content, _, bytes_read, had_errors = file_processor._read_text_stream(
+        io.BytesIO(data), 10, "utf-8", "test"
+    )
+    assert (content, bytes_read, had_errors) == ("a" * 9 + "\ufffd", 10, True)
+    with pytest.raises(UnicodeDecodeError):
+        file_processor._read_text_stream(io.BytesIO(data), 10, "utf-8", "test", strict=True)
+
+    monkeypatch.setattr(file_processor, "_MAX_TEXT_CONTENT_BYTES", 10)
+    file_path = tmp_path / "cut.txt"
+    file_path.write_bytes(data)
+    _, lenient = _process(file_path, hashing_enabled=False, encoding="utf-8")
+    _, strict = _process(file_path, hashing_enabled=False, encoding="utf-8", strict_decode=True)
+
+    assert lenient["had_decode_errors"] is True
+    assert "truncated" not in lenient
+    assert strict["reason"] == "decode_error"
+
+
+class _FakeZstd:
+    """Stand-in for ``zstandard`` that stores frames uncompressed after the magic."""
+
+    class ZstdError(Exception):
+        pass
+
+    class ZstdDecompressor:
+        def stream_reader(self, raw):
+            import io
+
+            raw.read(4)
+            return io.BytesIO(raw.read())
+
+
+def test_decompress_previews_zstd_content(monkeypatch, tmp_path: Path):
+    monkeypatch.setattr(file_processor, "zstandard", _FakeZstd)
+    monkeypatch.setattr(file_processor, "_MAX_TEXT_CONTENT_BYTES", 8)
+    file_path = tmp_path / "events.log.zst"
+    file_path.write_bytes(file_processor._ZSTD_MAGIC + b"line one\nline two\n")
+
+    _, info = _process(file_path, hashing_enabled=False, encoding="utf-8", decompress=True)
+
+    assert info["type"] == "text"
+    assert info["compression"] == "zstd"
+    assert info["content"] == "line one"
+    assert info["truncated"] is True
+
+
+def test_decompress_without_zstandard_leaves_file_untouched(monkeypatch, tmp_path: Path):
+    monkeypatch.setattr(file_processor, "zstandard", None)
+    file_path = tmp_path / "events.log.zst"
+    file_path.write_bytes(file_processor._ZSTD_MAGIC + b"\x00\x01payload")
+
+    _, info = _process(file_path, hashing_enabled=False, decompress=True)
+
+    assert "compression" not in info
+
+
+def test_truncated_previews_report_content_range(monkeypatch, tmp_path: Path):
+    text_path = tmp_path / "long.txt"
+    text_path.write_text("line\n" * 100, encoding="utf-8")
+    binary_path = tmp_path / "blob.bin"
+    binary_path.write_bytes(b"\x00\x01" * 512)
+    short_path = tmp_path / "short.txt"
+    short_path.write_text("short\n", encoding="utf-8")
+
+    monkeypatch.setattr(file_processor, "_MAX_TEXT_CONTENT_BYTES", 64)
+    _, text_info = _process(text_path, hashing_enabled=False)
+    _, binary_info = _process(binary_path, hashing_enabled=False, binary_preview_bytes=32)
+    _, short_info = _process(short_path, hashing_enabled=False)
+
+    assert text_info["content_range"] == [0, 64]
+    assert binary_info["content_range"] == [0, 32]
+    assert "content_range" not in short_info
+
+
+def test_include_mime_adds_mime_type(tmp_path: Path):
+    file_path = tmp_path / "data.json"
+    file_path.write_text('{"a": 1}\n', encoding="utf-8")
+
+    _, plain = _process(file_path, hashing_enabled=False)
+    _, with_mime = _process(file_path, hashing_enabled=False, include_mime=True)
+
+    assert "mime_type" not in plain
+    assert with_mime["mime_type"] == "application/json"
+
+
+@pytest.mark.skipif(not hasattr(os, "symlink"), reason="symlinks unsupported")
+def test_metadata_reports_symlinks(tmp_path: Path):
+    target = tmp_path / "target.txt"
+    target.write_text("target\n", encoding="utf-8")
+    link = tmp_path / "link.txt"
+    link.symlink_to(target)
+
+    _, target_info = _process(target, hashing_enabled=False)
+    _, link_info = _process(link, hashing_enabled=False)
+
+    assert target_info["is_symlink"] is False
+    assert link_info["is_symlink"] is True
+    assert link_info["content"] == "target\n"
+
+
+def test_metadata_reports_nanosecond_timestamps(tmp_path: Path):
+    file_path = tmp_path / "precise.txt"
+    file_path.write_text("precise\n", encoding="utf-8")
+    os.utime(file_path, ns=(1_700_000_000_123_456_789, 1_700_000_000_123_456_789))
+
+    _, info = _process(file_path, hashing_enabled=False)
+
+    assert info["modified_ns"] == 1_700_000_000_123_456_789
+    assert "created_ns" in info
+
+
+def test_compress_binary_deflates_only_when_smaller(tmp_path: Path):
+    import zlib
+
+    compressible = tmp_path / "zeros.bin"
+    compressible.write_bytes(b"\x00\x01" * 2048)
+    random_path = tmp_path / "noise.bin"
+    random_path.write_bytes(os.urandom(64) + b"\x00")
+
+    _, packed = _process(compressible, hashing_enabled=False, compress_binary=True)
+    _, noise = _process(random_path, hashing_enabled=False, compress_binary=True)
+
+    assert packed["encoding"] == "base64+deflate"
+    assert packed["preview_bytes"] == 4096
+    assert zlib.decompress(base64.b64decode(packed["content"])) == compressible.read_bytes()
+    assert noise["encoding"] == "base64"
+    assert base64.b64decode(noise["content"]) == random_path.read_bytes()
+
+
+def test_include_xattrs_reports_extended_attributes(tmp_path: Path):
+    file_path = tmp_path / "labelled.txt"
+    file_path.write_text("labelled\n", encoding="utf-8")
+    if not hasattr(os, "setxattr"):
+        pytest.skip("extended attributes unsupported")
+    try:
+        os.setxattr(file_path, "user.samuraizer", b"audit")
+    except OSError:
+        pytest.skip("filesystem does not support user extended attributes")
+
+    _, plain = _process(file_path, hashing_enabled=False)
+    _, info = _process(file_path, hashing_enabled=False, include_xattrs=True)
+
+    assert "xattrs" not in plain
+    assert base64.b64decode(info["xattrs"]["user.samuraizer"]) == b"audit"
+
+
+def test_doc_hint_reports_first_line_markers(tmp_path: Path):
+    page = tmp_path / "page.html"
+    page.write_text("<!DOCTYPE html>\n<html></html>\n", encoding="utf-8")
+    script = tmp_path / "tool.py"
+    script.write_text("#!/usr/bin/env python3\n# -*- coding: latin-1 -*-\n", encoding="utf-8")
+    notes = tmp_path / "notes.txt"
+    notes.write_text("just text\n", encoding="utf-8")
+
+    _, page_info = _process(page, hashing_enabled=False, doc_hint=True)
+    _, script_info = _process(script, hashing_enabled=False, doc_hint=True)
+    _, notes_info = _process(notes, hashing_enabled=False, doc_hint=True)
+    _, plain = _process(page, hashing_enabled=False)
+
+    assert page_info["doc_hint"] == "html"
+    assert script_info["doc_hint"] == "python-coding: latin-1"
+    assert "doc_hint" not in notes_info
+    assert "doc_hint" not in plain
+
+
+def test_zero_byte_files_are_reported_as_empty(tmp_path: Path):
+    file_path = tmp_path / "blank.txt"
+    file_path.write_bytes(b"")
+
+    _, info = _process(file_path, hashing_enabled=False)
+    _, buffer_info = file_processor.process_bytes(b"", "blank.txt", hashing_enabled=False)
+
+    assert info["type"] == "empty"
+    assert "content" not in info
+    assert info["size"] == 0
+    assert info["modified"] is not None
+    assert buffer_info["type"] == "empty"
+
+
+def test_preview_hash_covers_only_the_delivered_content(monkeypatch, tmp_path: Path):
+    text_path = tmp_path / "long.txt"
+    text_path.write_text("line\n" * 100, encoding="utf-8")
+    binary_path = tmp_path / "blob.bin"
+    binary_path.write_bytes(bytes(range(256)) * 4)
+
+    monkeypatch.setattr(file_processor, "_MAX_TEXT_CONTENT_BYTES", 64)
+    _, text = _process(text_path, hashing_enabled=False, preview_hash=True)
+    _, binary = _process(
+        binary_path,
+        hashing_enabled=False,
+        binary_preview_bytes=16,
+        compress_binary=True,
+        preview_hash=True,
+    )
+    _, plain = _process(text_path, hashing_enabled=False)
+
+    assert text["truncated"] is True
+    assert text["preview_hash"] == HashService.compute_bytes_hash(b"line\n" * 12 + b"line")
+    assert binary["preview_hash"] == HashService.compute_bytes_hash(bytes(range(16)))
+    assert "preview_hash" not in plain
+
+
+@pytest.mark.parametrize("size", [0, 1, 2, 3, 10, 11, 31])
+def test_streamed_base64_matches_one_shot_encoding(monkeypatch, size: int):
+    import io
+
+    monkeypatch.setattr(file_processor, "_STREAM_READ_CHUNK_SIZE", 5)
+    data = os.urandom(size)
+
+    content, bytes_read = file_processor._read_base64_stream(io.BytesIO(data + b"tail"), size)
+
+    assert bytes_read == size
+    assert content == base64.b64encode(data).decode("ascii")
+
+
+def test_read_text_lines_splits_without_line_breaks(tmp_path: Path):
+    file_path = tmp_path / "notes.txt"
+    file_path.write_bytes(b"one\r\ntwo\nthree\n")
+
+    full = file_processor.read_text_lines(file_path, max_lines=10, max_bytes=1024, encoding="utf-8")
+    capped = file_processor.read_text_lines(file_path, max_lines=2, max_bytes=1024, encoding="utf-8")
+    cut = file_processor.read_text_lines(file_path, max_lines=10, max_bytes=6, encoding="utf-8")
+
+    assert full["lines"] == ["one", "two", "three"]
+    assert full["truncated"] is False
+    assert capped["lines"] == ["one", "two"]
+    assert capped["truncated"] is True
+    assert cut["lines"] == ["one", "t"]
+    assert cut["truncated"] is True
+
+
+def test_hybrid_preview_stops_at_the_binary_tail(tmp_path: Path):
+    header = b"-----BEGIN CERTIFICATE-----\nMIIB\n"
+    mixed = tmp_path / "bundle.pem"
+    mixed.write_bytes(header + b"\xff\xfe\x00\x01binary")
+    options = {"hashing_enabled": False, "encoding": "utf-8", "assume_text": True}
+
+    _, plain = _process(mixed, **options)
+    _, hybrid = _process(mixed, hybrid_preview=True, **options)
+
+    assert "�" in plain["content"]
+    assert "binary_tail_offset" not in plain
+    assert hybrid["content"] == header.decode("ascii")
+    assert hybrid["binary_tail_offset"] == len(header)
+    assert hybrid["truncated"] is True
+
+
+def test_reencode_to_adds_the_preview_in_the_target_encoding(tmp_path: Path):
+    file_path = tmp_path / "latin.txt"
+    file_path.write_bytes("café\n".encode("latin-1"))
+
+    _, info = _process(file_path, hashing_enabled=False, encoding="latin-1", reencode_to="utf-8-sig")
+
+    assert info["content"] == "café\n"
+    assert base64.b64decode(info["content_bytes_b64"]) == "\ufeffcafé\n".encode("utf-8")
+    assert info["content_bytes_encoding"] == "utf-8-sig"
+
+
+@pytest.mark.parametrize(
+    ("name", "data", "kwargs"),
+    [
+        ("notes.txt", "line\n".encode("utf-8") * 400, {"encoding_sample_bytes": 16}),
+        ("blob.bin", bytes(range(256)) * 8, {"binary_preview_bytes": 64}),
+    ],
+)
+def test_single_pass_hash_hashes_the_whole_file_from_the_preview_reads(
+    fake_cache, monkeypatch, tmp_path: Path, name, data, kwargs
+):
+    file_path = tmp_path / name
+    file_path.write_bytes(data)
+    expected = HashService.compute_bytes_hash(data)
+
+    def unexpected_reopen(*_args, **_kwargs):
+        raise AssertionError("the file was hashed in a separate pass")
+
+    monkeypatch.setattr(HashService, "compute_file_hash", unexpected_reopen)
+
+    _, info = _process(file_path, hash_encoding="hex", single_pass_hash=True, **kwargs)
+
+    assert info["file_hash"] == expected
+    assert fake_cache[str(file_path.resolve())]["file_hash"] == expected
+
+
+def test_supplied_size_and_mtime_replace_the_stat(tmp_path: Path):
+    file_path = tmp_path / "notes.txt"
+    file_path.write_text("hello\n", encoding="utf-8")
+
+    _, info = _process(file_path, hashing_enabled=False, size=6, mtime=1_700_000_000.5)
+
+    assert info["content"] == "hello\n"
+    assert info["size"] == 6
+    assert info["modified_ns"] == 1_700_000_000_500_000_000
+    assert "permissions" not in info
+
+
+def test_supplied_size_decides_the_size_limit(tmp_path: Path):
+    file_path = tmp_path / "notes.txt"
+    file_path.write_text("hello\n", encoding="utf-8")
+
+    _, info = _process(file_path, hashing_enabled=False, size=2 * 1024 * 1024)
+
+    assert info == {"type": "excluded", "reason": "file_size", "size": 2 * 1024 * 1024}
+
+
+def test_supplied_size_alone_is_reported_in_the_metadata(tmp_path: Path):
+    file_path = tmp_path / "notes.txt"
+    file_path.write_text("hello\n", encoding="utf-8")
+
+    _, sized = _process(file_path, hashing_enabled=False, size=4)
+    _, dated = _process(file_path, hashing_enabled=False, mtime=1_700_000_000.5)
+
+    assert sized["size"] == 4
+    assert "permissions" in sized
+    assert dated["size"] == 6
+    assert dated["modified_ns"] == 1_700_000_000_500_000_000
+
+
+@pytest.mark.skipif(not hasattr(os, "mkfifo"), reason="FIFOs unsupported")
+def test_fifo_with_supplied_size_and_mtime_is_not_read(tmp_path: Path):
+    fifo = tmp_path / "pipe"
+    os.mkfifo(fifo)
+
+    _, info = _process(fifo, hashing_enabled=False, size=6, mtime=1_700_000_000.5)
+
+    assert info["type"] == "special"
+    assert info["kind"] == "fifo"
+    assert info["size"] == 6
+
+
+@pytest.mark.parametrize("kwargs", [{"size": -1}, {"mtime": -0.5}])
+def test_negative_supplied_stat_values_are_rejected(tmp_path: Path, kwargs):
+    file_path = tmp_path / "notes.txt"
+    file_path.write_text("hello\n", encoding="utf-8")
+
+    with pytest.raises(ValueError):
+        _process(file_path, hashing_enabled=False, **kwargs)
+
+
+def test_read_binary_bytes_returns_raw_data(tmp_path: Path):
+    file_path = tmp_path / "blob.bin"
+    file_path.write_bytes(bytes(range(256)))
+
+    data, info = file_processor.read_binary_bytes(file_path, 16)
+    whole, whole_info = file_processor.read_binary_bytes(file_path, 1024)
+
+    assert data == bytes(range(16))
+    assert info == {"type": "binary", "size": 256, "preview_bytes": 16, "truncated": True}
+    assert whole == bytes(range(256))
+    assert whole_info["truncated"] is False
+
+
+def test_read_binary_bytes_reports_unreadable_files(tmp_path: Path):
+    data, info = file_processor.read_binary_bytes(tmp_path / "missing.bin", 16)
+
+    assert data == b""
+    assert info["type"] == "error"
+
+
+def test_cached_entries_are_not_reused_under_other_preview_options(fake_cache, tmp_path: Path):
+    file_path = tmp_path / "blob.bin"
+    file_path.write_bytes(bytes(range(256)))
+
+    _, small = _process(file_path, binary_preview_bytes=16)
+    _, large = _process(file_path, binary_preview_bytes=64)
+    _, cached = _process(file_path, binary_preview_bytes=64)
+
+    assert small["preview_bytes"] == 16
+    assert large["preview_bytes"] == 64
+    assert cached == large
+
+
+def test_cached_entries_are_not_reused_under_other_magic_overrides(fake_cache, tmp_path: Path):
+    from samuraizer.utils.file_utils.mime_detection import ClassificationRules, parse_magic_overrides
+
+    file_path = tmp_path / "level.pak"
+    file_path.write_bytes(b"GAME" + b"plain looking text\n" * 10)
+    rules = ClassificationRules(
+        magic_overrides=parse_magic_overrides([{"hex_prefix": "47414d45", "is_binary": True}])
+    )
+
+    _, overridden = _process(file_path, classification_rules=rules)
+    _, default = _process(file_path)
+
+    assert overridden["type"] == "binary"
+    assert default["type"] == "text"
+
+
+def test_cached_entries_are_not_reused_under_other_textual_mime_types(
+    fake_cache, monkeypatch, tmp_path: Path
+):
+    from samuraizer.utils.file_utils import mime_detection
+
+    file_path = tmp_path / "manifest"
+    file_path.write_text("name: demo\n", encoding="utf-8")
+
+    class YamlMagic:
+        def from_buffer(self, _sample: bytes) -> str:
+            return "application/x-yaml"
+
+    monkeypatch.setattr(mime_detection, "analyse_sample", lambda _sample: None)
+    monkeypatch.setattr(mime_detection, "get_magic_instance", lambda: YamlMagic())
+    mime_detection.clear_mime_cache()
+    rules = mime_detection.ClassificationRules(textual_types=frozenset({"application/x-yaml"}))
+
+    _, textual = _process(file_path, classification_rules=rules)
+    _, default = _process(file_path)
+
+    assert textual["type"] == "text"
+    assert default["type"] == "binary"
@@ -0,0 +1,51 @@
+from __future__ import annotations
+
+import sys
+import time
+from pathlib import Path
+from types import SimpleNamespace
+
+import pytest
+
+ROOT = str(Path(__file__).resolve().parents[1])
+if ROOT not in sys.path:  # pragma: no cover - defensive path setup
+    sys.path.insert(0, ROOT)
+
+from samuraizer.utils.file_utils import file_times
+
+
+def test_get_birthtime_prefers_stat_birthtime(tmp_path: Path):
+    stat = SimpleNamespace(st_birthtime=1234.5)
+
+    assert file_times.get_birthtime(tmp_path, stat) == 1234.5
+
+
+def test_get_birthtime_uses_statx_on_linux(tmp_path: Path):
+    if not file_times.STATX_ENABLED:
+        pytest.skip("statx is only used on Linux")
+
+    file_path = tmp_path / "created.txt"
+    before = time.time()
+    file_path.write_text("hello\n", encoding="utf-8")
+
+    birthtime = file_times.get_birthtime(file_path, SimpleNamespace())
+    if birthtime is None:
+        pytest.skip("filesystem does not record birth times")
+
+    assert before - 5 <= birthtime <= time.time() + 5
+
+
+def test_format_timestamp_keeps_missing_values_null():
+    from datetime import timezone
+
+    from samuraizer.utils.time_utils import format_timestamp
+
+    assert format_timestamp(None) is None
+    assert format_timestamp("2024-01-01T00:00:00+00:00") == "2024-01-01T00:00:00+00:00"
+    assert format_timestamp(0, timezone.utc) == "1970-01-01T00:00:00+00:00"
+
+
+def test_get_birthtime_ns_prefers_integer_field(tmp_path: Path):
+    stat = SimpleNamespace(st_birthtime_ns=1_234_500_000_001, st_birthtime=1234.5)
+
+    assert file_times.get_birthtime_ns(tmp_path, stat) == 1_234_500_000_001
@@ -1,4 +1,6 @@
+import base64
 import logging
+import random
 
 from samuraizer.backend.analysis import hash_service
 
@@ -27,3 +29,94 @@ def test_hash_service_fallback_without_xxhash(monkeypatch, tmp_path, caplog):
         hash_service.HashService.compute_file_hash(file_path)
 
     assert not any("xxhash package is not installed" in record.message for record in caplog.records)
+
+
+def test_hash_encodings_describe_the_same_digest(tmp_path):
+    file_path = tmp_path / "sample.txt"
+    file_path.write_bytes(b"samuraizer")
+
+    service = hash_service.HashService
+    hex_digest = service.compute_file_hash(file_path)
+    raw = bytes.fromhex(hex_digest)
+
+    assert base64.b64decode(service.compute_file_hash(file_path, encoding="base64")) == raw
+    assert base64.urlsafe_b64decode(service.compute_file_hash(file_path, encoding="base64url")) == raw
+    assert int(service.compute_file_hash(file_path, encoding="dec")) == int(hex_digest, 16)
+
+
+def test_quick_hash_only_samples_head_tail_and_size(tmp_path):
+    window = 16
+    first = tmp_path / "first.bin"
+    second = tmp_path / "second.bin"
+    longer = tmp_path / "longer.bin"
+    first.write_bytes(b"H" * window + b"middle-one" + b"T" * window)
+    second.write_bytes(b"H" * window + b"middle-two" + b"T" * window)
+    longer.write_bytes(b"H" * window + b"middle-three" + b"T" * window)
+
+    quick = hash_service.HashService.compute_quick_hash
+
+    assert quick(first, window=window) == quick(second, window=window)
+    assert quick(first, window=window) != quick(longer, window=window)
+
+
+def test_in_memory_hash_cache_skips_unchanged_files(monkeypatch, tmp_path):
+    file_path = tmp_path / "cached.txt"
+    file_path.write_bytes(b"first")
+    hash_service.clear_hash_cache()
+    first = hash_service.HashService.compute_file_hash(file_path)
+
+    calls = []
+    registry = hash_service._HASH_REGISTRY
+
+    class CountingRegistry:
+        def create_hasher(self):
+            calls.append(1)
+            return registry.create_hasher()
+
+    monkeypatch.setattr(hash_service, "_HASH_REGISTRY", CountingRegistry())
+
+    assert hash_service.HashService.compute_file_hash(file_path) == first
+    assert calls == []
+
+    file_path.write_bytes(b"second, longer")
+    assert hash_service.HashService.compute_file_hash(file_path) != first
+    assert len(calls) == 1
+
+    hash_service.configure_hash_cache(0)
+    try:
+        hash_service.HashService.compute_file_hash(file_path)
+        assert len(calls) == 2
+    finally:
+        hash_service.configure_hash_cache(hash_service._DEFAULT_HASH_CACHE_CAPACITY)
+
+
+def test_chunk_hashes_survive_insertions(tmp_path):
+    rng = random.Random(1234)
+    payload = bytes(rng.getrandbits(8) for _ in range(200_000))
+    original = tmp_path / "original.bin"
+    shifted = tmp_path / "shifted.bin"
+    original.write_bytes(payload)
+    shifted.write_bytes(b"inserted bytes" + payload)
+
+    before = hash_service.HashService.compute_chunk_hashes(original, avg_chunk_size=4096)
+    after = hash_service.HashService.compute_chunk_hashes(shifted, avg_chunk_size=4096)
+
+    assert sum(chunk["length"] for chunk in before) == len(payload)
+    assert [chunk["offset"] for chunk in before][:2] == [0, before[0]["length"]]
+    assert all(1024 <= chunk["length"] <= 4096 * 8 for chunk in before[:-1])
+    unchanged = {chunk["hash"] for chunk in before} & {chunk["hash"] for chunk in after}
+    assert len(unchanged) >= len(before) - 2
+
+
+def test_incremental_hasher_matches_file_hash(tmp_path):
+    data = bytes(random.Random(7).getrandbits(8) for _ in range(200_000))
+    file_path = tmp_path / "stream.bin"
+    file_path.write_bytes(data)
+
+    hasher = hash_service.IncrementalHasher()
+    for start in range(0, len(data), 4_099):
+        hasher.update(data[start : start + 4_099])
+
+    service = hash_service.HashService
+    assert hasher.hexdigest() == service.compute_file_hash(file_path, use_cache=False)
+    assert hasher.digest("base64") == service.compute_file_hash(file_path, encoding="base64")
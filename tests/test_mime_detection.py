@@ -23,6 +23,7 @@ class _FakeMagic:
 sys.modules.setdefault("magic", SimpleNamespace(Magic=_FakeMagic))
 
 from samuraizer.utils.file_utils import mime_detection
+from samuraizer.utils.file_utils.file_helpers import detect_wide_text_encoding
 
 
 @pytest.fixture(autouse=True)
@@ -70,3 +71,143 @@ def test_is_binary_falls_back_when_magic_fails(monkeypatch: pytest.MonkeyPatch,
     monkeypatch.setattr(mime_detection, "get_magic_instance", lambda: FailingMagic())
 
     assert mime_detection.is_binary(file_path) is True
+
+
+def test_configured_textual_mime_types_extend_builtins(monkeypatch: pytest.MonkeyPatch, tmp_path: Path):
+    file_path = tmp_path / "manifest"
+    file_path.write_text("name: demo\n", encoding="utf-8")
+
+    class YamlMagic:
+        def from_buffer(self, _sample: bytes) -> str:
+            return "application/x-yaml"
+
+    monkeypatch.setattr(mime_detection, "analyse_sample", lambda _sample: None)
+    monkeypatch.setattr(mime_detection, "get_magic_instance", lambda: YamlMagic())
+
+    prefixes, types = mime_detection.parse_textual_mime_types(prefixes=[" Application/X-YAML "])
+    rules = mime_detection.ClassificationRules(textual_prefixes=prefixes, textual_types=types)
+
+    assert mime_detection.is_binary(file_path) is True
+    assert mime_detection.is_binary(file_path, rules=rules) is False
+    assert mime_detection.is_binary(file_path) is True
+    assert mime_detection._mime_implies_text("text/plain", rules) is True
+
+
+def test_svg_is_text_but_svgz_is_binary(tmp_path: Path):
+    svg_path = tmp_path / "logo.svg"
+    svg_path.write_text("<svg xmlns='http://www.w3.org/2000/svg'><rect/></svg>\n", encoding="utf-8")
+    svgz_path = tmp_path / "logo.svgz"
+    svgz_path.write_bytes(b"\x1f\x8b\x08\x00")
+
+    assert mime_detection.is_binary(svg_path) is False
+    assert mime_detection.is_binary(svgz_path) is True
+    assert mime_detection._mime_implies_text("image/svg+xml") is True
+
+
+def test_utf16_without_bom_is_text(tmp_path: Path):
+    text = "Plain text saved as UTF-16LE without a byte order mark.\r\n" * 8
+    file_path = tmp_path / "notes.dat"
+    file_path.write_bytes(text.encode("utf-16-le"))
+
+    assert mime_detection.is_binary(file_path) is False
+    assert detect_wide_text_encoding(text.encode("utf-16-le")) == "utf-16-le"
+    assert detect_wide_text_encoding(text.encode("utf-32-be")) == "utf-32-be"
+
+
+def test_nul_heavy_binary_is_not_mistaken_for_utf16(tmp_path: Path):
+    file_path = tmp_path / "table.dat"
+    file_path.write_bytes(bytes(range(256)) * 4 + b"\x00\x00" * 256)
+
+    assert mime_detection.is_binary(file_path) is True
+
+
+def test_mime_cache_stats_count_hits_and_misses(tmp_path: Path):
+    file_path = tmp_path / "example.py"
+    file_path.write_text("print('hello')\n", encoding="utf-8")
+
+    mime_detection.is_binary(file_path)
+    mime_detection.is_binary(file_path)
+
+    stats = mime_detection.mime_cache_stats()
+    assert stats["hits"] == 1
+    assert stats["misses"] == 1
+    assert stats["len"] == 1
+    assert stats["capacity"] == 4096
+
+
+def test_content_first_detects_renamed_archive(tmp_path: Path):
+    import zipfile
+
+    archive = tmp_path / "notes.txt"
+    with zipfile.ZipFile(archive, "w") as bundle:
+        bundle.writestr("payload.bin", bytes(range(256)) * 8)
+
+    assert mime_detection.is_binary(archive) is False
+    assert mime_detection.is_binary(archive, content_first=True) is True
+    assert mime_detection.is_binary_bytes(archive.read_bytes(), "notes.txt", content_first=True) is True
+
+
+def test_classify_file_reports_mime_type_only_on_request(tmp_path: Path):
+    script = tmp_path / "tool.py"
+    script.write_text("print('hi')\n", encoding="utf-8")
+    unknown = tmp_path / "README"
+    unknown.write_text("plain words\n", encoding="utf-8")
+
+    assert mime_detection.classify_file(script) == (False, None)
+    assert mime_detection.classify_file(script, include_mime=True) == (False, "text/x-python")
+    assert mime_detection.classify_file(unknown, include_mime=True) == (False, "text/plain")
+
+
+def test_magic_overrides_take_precedence_in_order(tmp_path: Path):
+    asset = tmp_path / "level.pak"
+    asset.write_bytes(b"GAME" + b"plain looking text\n" * 10)
+    rules = mime_detection.ClassificationRules(
+        magic_overrides=mime_detection.parse_magic_overrides(
+            [
+                {"hex_prefix": "47414d45", "is_binary": True},
+                {"hex_prefix": "4741", "is_binary": False},
+            ]
+        )
+    )
+
+    assert mime_detection.is_binary(asset) is False
+    assert mime_detection.is_binary(asset, rules=rules) is True
+    assert mime_detection.is_binary_bytes(asset.read_bytes(), "level.pak", rules=rules) is True
+    # The rules only apply to the calls they are passed to, cached or not.
+    assert mime_detection.is_binary(asset) is False
+    assert mime_detection.is_binary_bytes(asset.read_bytes(), "level.pak") is False
+
+    with pytest.raises(ValueError):
+        mime_detection.parse_magic_overrides([{"hex_prefix": "zz", "is_binary": True}])
+
+
+def test_classify_many_fills_the_shared_cache(tmp_path: Path):
+    script = tmp_path / "tool.py"
+    script.write_text("print('hi')\n", encoding="utf-8")
+    blob = tmp_path / "blob.dat"
+    blob.write_bytes(b"\x00\x01\x02\x03" * 64)
+    missing = tmp_path / "missing.txt"
+
+    results = mime_detection.classify_many([script, str(blob), missing], max_workers=2)
+
+    assert results[str(script)] is False
+    assert results[str(blob)] is True
+    assert "error" in results[str(missing)]
+    assert mime_detection.is_binary(blob) is True
+    assert mime_detection.mime_cache_stats()["hits"] == 1
+
+
+def test_extension_only_skips_stat_and_cache_for_known_extensions(tmp_path: Path):
+    missing = tmp_path / "never-written.png"
+
+    assert mime_detection.is_binary(missing, extension_only=True) is True
+    assert mime_detection.is_binary(tmp_path / "absent.py", extension_only=True) is False
+    assert mime_detection.mime_cache_stats()["misses"] == 0
+
+
+def test_extension_only_still_reads_unknown_extensions(tmp_path: Path):
+    file_path = tmp_path / "data.unknownext"
+    file_path.write_bytes(b"\x00\x01\x02\x03" * 64)
+
+    assert mime_detection.is_binary(file_path, extension_only=True) is True
+    assert mime_detection.mime_cache_stats()["misses"] == 1
@@ -0,0 +1,233 @@
+from __future__ import annotations
+
+import os
+import sys
+from pathlib import Path
+
+import pytest
+
+ROOT = str(Path(__file__).resolve().parents[1])
+if ROOT not in sys.path:  # pragma: no cover - defensive path setup
+    sys.path.insert(0, ROOT)
+
+from samuraizer.backend.analysis.traversal.traversal_core import traverse_and_collect
+
+
+def _collect(root: Path, **kwargs):
+    options = {
+        "excluded_folders": set(),
+        "excluded_files": set(),
+        "exclude_patterns": [],
+        "follow_symlinks": False,
+    }
+    options.update(kwargs)
+    iterator, counters = traverse_and_collect(root, **options)
+    return sorted(path.relative_to(root).as_posix() for path in iterator), counters
+
+
+@pytest.mark.skipif(not hasattr(os, "symlink"), reason="symlinks unsupported")
+def test_follow_file_symlinks_guards_against_escaping_root(tmp_path: Path):
+    root = tmp_path / "repo"
+    outside = tmp_path / "outside"
+    root.mkdir()
+    outside.mkdir()
+    (root / "config.toml").write_text("key = 1\n", encoding="utf-8")
+    (outside / "secret.txt").write_text("secret\n", encoding="utf-8")
+    (outside / "nested.txt").write_text("nested\n", encoding="utf-8")
+
+    try:
+        (root / "linked.toml").symlink_to(root / "config.toml")
+        (root / "escape.txt").symlink_to(outside / "secret.txt")
+        (root / "linked_dir").symlink_to(outside, target_is_directory=True)
+    except OSError:
+        pytest.skip("symlink creation not permitted")
+
+    files, counters = _collect(root, follow_file_symlinks=True)
+
+    assert files == ["config.toml", "linked.toml"]
+    assert [item["file"] for item in counters.escaped_symlinks] == [str(root / "escape.txt")]
+    assert counters.excluded == 1
+
+
+def test_custom_ignore_files_compose_with_gitignore(tmp_path: Path):
+    (tmp_path / ".gitignore").write_text("*.log\nbuild/\n", encoding="utf-8")
+    (tmp_path / ".samuraizerignore").write_text("!keep.log\n/secrets.txt\n", encoding="utf-8")
+    (tmp_path / "app.log").write_text("log\n", encoding="utf-8")
+    (tmp_path / "keep.log").write_text("log\n", encoding="utf-8")
+    (tmp_path / "secrets.txt").write_text("secret\n", encoding="utf-8")
+    (tmp_path / "build").mkdir()
+    (tmp_path / "build" / "out.txt").write_text("out\n", encoding="utf-8")
+    nested = tmp_path / "src"
+    nested.mkdir()
+    (nested / "secrets.txt").write_text("not anchored here\n", encoding="utf-8")
+    (nested / ".samuraizerignore").write_text("generated.py\n", encoding="utf-8")
+    (nested / "generated.py").write_text("x = 1\n", encoding="utf-8")
+    (nested / "main.py").write_text("print('hi')\n", encoding="utf-8")
+
+    files, counters = _collect(tmp_path, ignore_files=[".gitignore", ".samuraizerignore"])
+
+    assert files == [
+        ".gitignore",
+        ".samuraizerignore",
+        "keep.log",
+        "src/.samuraizerignore",
+        "src/main.py",
+        "src/secrets.txt",
+    ]
+    assert counters.excluded == 3
+
+
+def test_counters_split_name_and_pattern_exclusions(tmp_path: Path):
+    (tmp_path / "keep.txt").write_text("keep\n", encoding="utf-8")
+    (tmp_path / "package-lock.json").write_text("{}\n", encoding="utf-8")
+    (tmp_path / "module.pyc").write_bytes(b"\x00")
+    (tmp_path / "other.pyc").write_bytes(b"\x00")
+
+    files, counters = _collect(
+        tmp_path,
+        excluded_files={"package-lock.json"},
+        exclude_patterns=["*.pyc"],
+    )
+
+    assert files == ["keep.txt"]
+    assert counters.excluded_by_name == 1
+    assert counters.excluded_by_pattern == 2
+    assert counters.excluded == 3
+
+
+def test_skip_hidden_prunes_dot_directories_and_counts_dotfiles(tmp_path: Path):
+    (tmp_path / "visible.txt").write_text("a\n", encoding="utf-8")
+    (tmp_path / ".env").write_text("SECRET=1\n", encoding="utf-8")
+    (tmp_path / ".cache").mkdir()
+    (tmp_path / ".cache" / "blob.txt").write_text("b\n", encoding="utf-8")
+
+    files, counters = _collect(tmp_path)
+    assert files == [".cache/blob.txt", ".env", "visible.txt"]
+
+    files, counters = _collect(tmp_path, skip_hidden=True)
+    assert files == ["visible.txt"]
+    assert counters.excluded_reasons == {"hidden": 1}
+
+
+def test_unreadable_directories_are_reported_as_walk_errors(monkeypatch, tmp_path: Path):
+    (tmp_path / "ok.txt").write_text("ok\n", encoding="utf-8")
+    locked = tmp_path / "locked"
+    locked.mkdir()
+    (locked / "secret.txt").write_text("s\n", encoding="utf-8")
+
+    original_iterdir = Path.iterdir
+
+    def iterdir(self):
+        if self == locked:
+            raise PermissionError(13, "Permission denied", str(self))
+        return original_iterdir(self)
+
+    monkeypatch.setattr(Path, "iterdir", iterdir)
+
+    files, counters = _collect(tmp_path)
+
+    assert files == ["ok.txt"]
+    assert [error["path"] for error in counters.walk_errors] == [str(locked)]
+    assert "Permission denied" in counters.walk_errors[0]["error"]
+
+
+def test_compiled_filters_replace_raw_exclusion_lists(tmp_path: Path):
+    from samuraizer.backend.services.pattern_service import CompiledFilters
+
+    (tmp_path / "node_modules").mkdir()
+    (tmp_path / "node_modules" / "dep.js").write_text("x\n", encoding="utf-8")
+    (tmp_path / "app.py").write_text("a\n", encoding="utf-8")
+    (tmp_path / "app.pyc").write_bytes(b"\x00")
+    (tmp_path / "build.tmp").write_text("t\n", encoding="utf-8")
+    (tmp_path / "Thumbs.db").write_bytes(b"\x00")
+
+    filters = CompiledFilters(
+        excluded_folders={"node_modules"},
+        excluded_files={"Thumbs.db"},
+        exclude_patterns=["*.pyc", r"regex:^build\."],
+    )
+
+    for _ in range(2):
+        files, counters = _collect(tmp_path, filters=filters)
+        assert files == ["app.py"]
+        assert counters.excluded_reasons == {"file_name": 1, "pattern": 2}
+
+
+def test_strict_patterns_reject_invalid_patterns(tmp_path: Path):
+    (tmp_path / "a.txt").write_text("a\n", encoding="utf-8")
+
+    files, _ = _collect(tmp_path, exclude_patterns=["regex:(unclosed", "*.[ch"])
+    assert files == ["a.txt"]
+
+    with pytest.raises(ValueError, match=r"regex:\(unclosed"):
+        _collect(tmp_path, exclude_patterns=["regex:(unclosed"], strict_patterns=True)
+    with pytest.raises(ValueError, match="unterminated character set"):
+        _collect(tmp_path, exclude_patterns=["*.[ch"], strict_patterns=True)
+
+    files, _ = _collect(tmp_path, exclude_patterns=["*.[ch]", "[]]x"], strict_patterns=True)
+    assert files == ["a.txt"]
+
+
+def test_gather_threads_keep_the_walk_order(tmp_path: Path):
+    for directory in ("a", "a/deep", "b", "c"):
+        (tmp_path / directory).mkdir()
+        for index in range(3):
+            (tmp_path / directory / f"{index}.txt").write_text("x\n", encoding="utf-8")
+    options = {
+        "excluded_folders": set(),
+        "excluded_files": set(),
+        "exclude_patterns": [],
+        "follow_symlinks": False,
+    }
+
+    serial, _ = traverse_and_collect(tmp_path, **options)
+    parallel, counters = traverse_and_collect(tmp_path, gather_threads=4, **options)
+
+    assert list(parallel) == list(serial)
+    assert counters.included == 12
+
+
+def test_negated_patterns_re_include_earlier_exclusions(tmp_path: Path):
+    from samuraizer.backend.services.pattern_service import matches_patterns
+
+    for name in ("app.log", "keep.log", "debug.txt", "!bang.txt"):
+        (tmp_path / name).write_text("x\n", encoding="utf-8")
+    patterns = ["*.log", "regex:debug", "!keep.log", "!regex:deb", "\\!bang*"]
+
+    files, counters = _collect(tmp_path, exclude_patterns=patterns)
+
+    assert files == ["debug.txt", "keep.log"]
+    assert counters.excluded_reasons == {"pattern": 2}
+    assert [matches_patterns(name, patterns) for name in ("app.log", "keep.log", "debug.txt")] == [
+        True,
+        False,
+        False,
+    ]
+    assert matches_patterns("keep.log", ["!keep.log", "*.log"]) is True
+
+
+def test_path_prefix_only_enters_the_subtree(monkeypatch, tmp_path: Path):
+    for relative in ("top.txt", "src/app/main.py", "src/app/sub/util.py", "src/lib/x.py", "docs/a.md"):
+        (tmp_path / relative).parent.mkdir(parents=True, exist_ok=True)
+        (tmp_path / relative).write_text("x\n", encoding="utf-8")
+
+    listed = []
+    original_iterdir = Path.iterdir
+
+    def iterdir(self):
+        listed.append(self.relative_to(tmp_path).as_posix())
+        return original_iterdir(self)
+
+    monkeypatch.setattr(Path, "iterdir", iterdir)
+
+    files, counters = _collect(tmp_path, path_prefix="src/app")
+
+    assert files == ["src/app/main.py", "src/app/sub/util.py"]
+    assert sorted(listed) == [".", "src", "src/app", "src/app/sub"]
+    assert counters.excluded == 0
+
+
+@pytest.mark.parametrize("prefix", ["../outside", "/abs"])
+def test_path_prefix_must_stay_inside_the_root(tmp_path: Path, prefix):
+    with pytest.raises(ValueError):
+        _collect(tmp_path, path_prefix=prefix)
@@ -0,0 +1,1040 @@
+from __future__ import annotations
+
+import json
+import os
+import sys
+import threading
+from pathlib import Path
+from types import SimpleNamespace
+
+import pytest
+
+ROOT = str(Path(__file__).resolve().parents[1])
+if ROOT not in sys.path:  # pragma: no cover - defensive path setup
+    sys.path.insert(0, ROOT)
+
+
+class _FakeMagic:
+    def __init__(self, mime: bool = True) -> None:  # pragma: no cover - simple stub
+        self.mime = mime
+
+    def from_buffer(self, _: bytes) -> str:  # pragma: no cover - simple stub
+        return "text/plain"
+
+
+sys.modules.setdefault("magic", SimpleNamespace(Magic=_FakeMagic))
+
+from samuraizer.backend.analysis.hash_service import HashService
+from samuraizer.backend.analysis.traversal import traversal_processor
+
+
+def _run(root: Path, **kwargs):
+    options = {
+        "root_dir": root,
+        "max_file_size": 1024 * 1024,
+        "include_binary": True,
+        "excluded_folders": set(),
+        "excluded_files": set(),
+        "follow_symlinks": False,
+        "image_extensions": set(),
+        "exclude_patterns": [],
+        "threads": 2,
+        "hashing_enabled": False,
+    }
+    options.update(kwargs)
+    return traversal_processor.get_directory_structure(**options)
+
+
+def test_file_timeout_reports_error_and_continues(monkeypatch, tmp_path: Path):
+    (tmp_path / "fast.txt").write_text("fast\n", encoding="utf-8")
+    (tmp_path / "slow.txt").write_text("slow\n", encoding="utf-8")
+    release = threading.Event()
+    original = traversal_processor.process_file
+
+    def fake_process_file(file_path: Path, *args, **kwargs):
+        if file_path.name == "slow.txt":
+            release.wait(5)
+        return original(file_path, *args, **kwargs)
+
+    monkeypatch.setattr(traversal_processor, "process_file", fake_process_file)
+
+    try:
+        structure, summary = _run(tmp_path, file_timeout_ms=100)
+    finally:
+        release.set()
+
+    assert structure["fast.txt"]["type"] == "text"
+    assert structure["slow.txt"]["type"] == "error"
+    assert structure["slow.txt"]["reason"] == "timeout"
+    assert [item["file"] for item in summary["failed_files"]] == [str(tmp_path / "slow.txt")]
+    assert summary["processed_files"] == 2
+
+
+def test_hung_files_do_not_starve_a_single_worker(monkeypatch, tmp_path: Path):
+    for name in ("hung1.txt", "hung2.txt", "ok1.txt", "ok2.txt"):
+        (tmp_path / name).write_text(name, encoding="utf-8")
+    release = threading.Event()
+    original = traversal_processor.process_file
+
+    def fake_process_file(file_path: Path, *args, **kwargs):
+        if file_path.name.startswith("hung"):
+            release.wait(10)
+        return original(file_path, *args, **kwargs)
+
+    monkeypatch.setattr(traversal_processor, "process_file", fake_process_file)
+
+    try:
+        structure, summary = _run(
+            tmp_path, threads=1, max_open_files=1, sort_by="name", file_timeout_ms=100
+        )
+    finally:
+        release.set()
+
+    assert structure["hung1.txt"]["reason"] == "timeout"
+    assert structure["hung2.txt"]["reason"] == "timeout"
+    assert structure["ok1.txt"]["type"] == "text"
+    assert structure["ok2.txt"]["type"] == "text"
+    assert summary["failed_count"] == 2
+
+
+def test_summary_reports_excluded_breakdown(tmp_path: Path):
+    (tmp_path / "keep.txt").write_text("keep\n", encoding="utf-8")
+    (tmp_path / "skip.tmp").write_text("tmp\n", encoding="utf-8")
+    (tmp_path / "notes.bak").write_text("bak\n", encoding="utf-8")
+    (tmp_path / "large.txt").write_text("x" * 2048, encoding="utf-8")
+    (tmp_path / "image.png").write_bytes(b"\x89PNG\r\n\x1a\n")
+
+    _, summary = _run(
+        tmp_path,
+        max_file_size=1024,
+        include_binary=False,
+        excluded_files={"skip.tmp"},
+        exclude_patterns=["*.bak"],
+    )
+
+    assert summary["excluded_by_name"] == 1
+    assert summary["excluded_by_pattern"] == 1
+    assert summary["excluded_breakdown"] == {
+        "file_name": 1,
+        "pattern": 1,
+        "file_size": 1,
+        "binary_or_image": 1,
+    }
+
+
+def test_group_by_parent_emits_one_message_per_directory(tmp_path: Path):
+    for directory in ("", "a", "a/b", "c"):
+        folder = tmp_path / directory
+        folder.mkdir(parents=True, exist_ok=True)
+        for index in range(3):
+            (folder / f"file{index}.txt").write_text(f"{directory}{index}\n", encoding="utf-8")
+
+    payloads = list(
+        traversal_processor.generate_directory_chunks(
+            root_dir=tmp_path,
+            max_file_size=1024 * 1024,
+            include_binary=False,
+            excluded_folders=set(),
+            excluded_files=set(),
+            follow_symlinks=False,
+            image_extensions=set(),
+            exclude_patterns=[],
+            threads=2,
+            encoding=None,
+            hashing_enabled=False,
+            progress_callback=None,
+            cancellation_token=None,
+            chunk_size=1,
+            max_pending_tasks=2,
+            group_by_parent=True,
+        )
+    )
+
+    groups = [payload for payload in payloads if "entries" in payload]
+    assert sorted(group["parent"] for group in groups) == ["", "a", "a/b", "c"]
+    for group in groups:
+        assert len(group["entries"]) == 3
+        assert {entry["parent"] for entry in group["entries"]} == {group["parent"]}
+        for entry in group["entries"]:
+            expected = f"{group['parent']}/{entry['filename']}" if group["parent"] else entry["filename"]
+            assert entry["relative_path"] == expected
+    assert "summary" in payloads[-1]
+
+
+def test_io_concurrency_reads_ahead_of_the_processing_workers(monkeypatch, tmp_path: Path):
+    from samuraizer.backend.analysis.traversal import read_ahead
+
+    for index in range(8):
+        (tmp_path / f"file{index}.txt").write_text("data\n", encoding="utf-8")
+
+    lock = threading.Lock()
+    reading = processing = 0
+    read_peak = processing_peak = 0
+    barrier = threading.Barrier(4, timeout=5)
+    original_read = read_ahead.read_ahead
+    original_process = traversal_processor.process_file
+
+    def slow_read(file_path: Path, limit: int) -> int:
+        nonlocal reading, read_peak
+        with lock:
+            reading += 1
+            read_peak = max(read_peak, reading)
+        try:
+            barrier.wait()
+        except threading.BrokenBarrierError:
+            pass
+        with lock:
+            reading -= 1
+        return original_read(file_path, limit)
+
+    def counting_process_file(*args, **kwargs):
+        nonlocal processing, processing_peak
+        with lock:
+            processing += 1
+            processing_peak = max(processing_peak, processing)
+        try:
+            return original_process(*args, **kwargs)
+        finally:
+            with lock:
+                processing -= 1
+
+    monkeypatch.setattr(read_ahead, "read_ahead", slow_read)
+    monkeypatch.setattr(traversal_processor, "process_file", counting_process_file)
+
+    structure, _summary = _run(tmp_path, threads=1, io_concurrency=4)
+
+    assert len(structure) == 8
+    assert all(info["content"] == "data\n" for info in structure.values())
+    assert read_peak >= 4
+    assert processing_peak == 1
+
+    with pytest.raises(ValueError):
+        _run(tmp_path, io_concurrency=0)
+
+
+def test_read_ahead_skips_files_above_the_limit(tmp_path: Path):
+    from samuraizer.backend.analysis.traversal.read_ahead import read_ahead
+
+    file_path = tmp_path / "data.bin"
+    file_path.write_bytes(b"x" * 100)
+
+    assert read_ahead(file_path, 100) == 100
+    assert read_ahead(file_path, 99) == 0
+    assert read_ahead(tmp_path / "missing.bin", 100) == 0
+
+
+def test_dry_run_counts_files_without_reading_them(monkeypatch, tmp_path: Path):
+    (tmp_path / "a.txt").write_text("12345", encoding="utf-8")
+    (tmp_path / "sub").mkdir()
+    (tmp_path / "sub" / "b.txt").write_text("123", encoding="utf-8")
+    (tmp_path / "skip.log").write_text("ignored", encoding="utf-8")
+
+    def fail_process_file(*_args, **_kwargs):
+        raise AssertionError("dry run must not process files")
+
+    monkeypatch.setattr(traversal_processor, "process_file", fail_process_file)
+
+    structure, summary = _run(tmp_path, exclude_patterns=["*.log"], dry_run=True)
+
+    assert structure == {}
+    assert summary["dry_run"] is True
+    assert summary["included_files"] == 2
+    assert summary["total_bytes"] == 8
+    assert summary["excluded_breakdown"] == {"pattern": 1}
+
+
+def test_absolute_paths_are_opt_in(tmp_path: Path):
+    (tmp_path / "sub").mkdir()
+    (tmp_path / "sub" / "file.txt").write_text("data\n", encoding="utf-8")
+    collected = []
+
+    _run(tmp_path, chunk_callback=collected.extend)
+    _run(tmp_path, chunk_callback=collected.extend, absolute_paths=True)
+
+    plain, absolute = collected
+    assert "absolute_path" not in plain
+    assert absolute["absolute_path"] == str((tmp_path / "sub" / "file.txt").resolve())
+
+
+def test_manifest_hash_tracks_tree_content(monkeypatch, tmp_path: Path):
+    from samuraizer.backend.analysis import file_processor
+
+    monkeypatch.setattr(file_processor, "is_cache_disabled", lambda: True)
+    (tmp_path / "sub").mkdir()
+    (tmp_path / "a.txt").write_text("alpha\n", encoding="utf-8")
+    (tmp_path / "sub" / "b.txt").write_text("beta\n", encoding="utf-8")
+
+    structure, first = _run(tmp_path, hashing_enabled=True)
+    _, second = _run(tmp_path, hashing_enabled=True, threads=1)
+    (tmp_path / "sub" / "b.txt").write_text("gamma\n", encoding="utf-8")
+    _, changed = _run(tmp_path, hashing_enabled=True)
+
+    assert "file_hash" not in structure["a.txt"]
+    assert first["manifest_hash"] == second["manifest_hash"]
+    assert first["manifest_hash"] != changed["manifest_hash"]
+
+
+def test_filter_callback_drops_entries(tmp_path: Path):
+    (tmp_path / "keep.txt").write_text("keep me\n", encoding="utf-8")
+    (tmp_path / "drop.txt").write_text("TODO: drop me\n", encoding="utf-8")
+
+    def without_todos(entry):
+        return "TODO" not in entry["info"].get("content", "")
+
+    structure, summary = _run(tmp_path, filter_callback=without_todos)
+
+    assert list(structure) == ["keep.txt"]
+    assert summary["excluded_breakdown"] == {"filter": 1}
+
+
+def test_sort_by_controls_processing_order(tmp_path: Path):
+    (tmp_path / "b").mkdir()
+    (tmp_path / "c.txt").write_text("1", encoding="utf-8")
+    (tmp_path / "a.txt").write_text("333", encoding="utf-8")
+    (tmp_path / "b" / "big.txt").write_text("55555", encoding="utf-8")
+
+    def order(sort_by):
+        collected = []
+        _run(tmp_path, threads=1, max_pending_tasks=1, sort_by=sort_by, chunk_callback=collected.extend)
+        return [entry["relative_path"] for entry in collected]
+
+    assert order("path") == ["a.txt", "b/big.txt", "c.txt"]
+    assert order("size") == ["c.txt", "a.txt", "b/big.txt"]
+    assert order("name") == ["a.txt", "b/big.txt", "c.txt"]
+
+
+def test_get_directory_summary_discards_entries(tmp_path: Path):
+    (tmp_path / "a.txt").write_text("a\n", encoding="utf-8")
+    (tmp_path / "b.log").write_text("b\n", encoding="utf-8")
+
+    summary = traversal_processor.get_directory_summary(
+        root_dir=tmp_path,
+        max_file_size=1024,
+        include_binary=True,
+        excluded_folders=set(),
+        excluded_files=set(),
+        follow_symlinks=False,
+        image_extensions=set(),
+        exclude_patterns=["*.log"],
+        threads=1,
+        hashing_enabled=False,
+    )
+
+    assert summary["included_files"] == 1
+    assert summary["excluded_files"] == 1
+    assert summary["processed_files"] == 1
+
+
+def test_content_budget_replaces_content_once_exhausted(tmp_path: Path):
+    for name in ("a.txt", "b.txt", "c.txt"):
+        (tmp_path / name).write_text("x" * 10, encoding="utf-8")
+
+    collected = []
+    _, summary = _run(
+        tmp_path,
+        threads=1,
+        max_pending_tasks=1,
+        sort_by="name",
+        max_total_content_bytes=15,
+        chunk_callback=collected.extend,
+    )
+    infos = {entry["filename"]: entry["info"] for entry in collected}
+
+    assert infos["a.txt"]["content"] == "x" * 10
+    assert infos["b.txt"]["content"] == "x" * 10
+    assert infos["c.txt"]["type"] == "excluded"
+    assert infos["c.txt"]["reason"] == "content_budget"
+    assert infos["c.txt"]["size"] == 10
+    assert summary["content_budget_exhausted"] is True
+    assert summary["excluded_breakdown"] == {"content_budget": 1}
+
+
+def test_gather_paths_lists_files_without_processing(monkeypatch, tmp_path: Path):
+    (tmp_path / "src").mkdir()
+    (tmp_path / "src" / "main.py").write_text("print()\n", encoding="utf-8")
+    (tmp_path / "debug.log").write_text("noise\n", encoding="utf-8")
+
+    def fail(*_args, **_kwargs):
+        raise AssertionError("gather_paths must not process files")
+
+    monkeypatch.setattr(traversal_processor, "process_file", fail)
+
+    result = traversal_processor.gather_paths(tmp_path, set(), set(), ["*.log"], False)
+
+    assert result == {
+        "files": [str(tmp_path / "src" / "main.py")],
+        "included": 1,
+        "excluded": 1,
+        "cancelled": False,
+    }
+
+
+def test_multiple_roots_merge_into_one_result(tmp_path: Path):
+    frontend = tmp_path / "frontend"
+    backend = tmp_path / "backend"
+    (frontend / "src").mkdir(parents=True)
+    backend.mkdir()
+    (frontend / "src" / "app.js").write_text("app\n", encoding="utf-8")
+    (backend / "main.py").write_text("main\n", encoding="utf-8")
+    (backend / "debug.log").write_text("log\n", encoding="utf-8")
+
+    collected = []
+    structure, summary = _run(
+        [frontend, backend],
+        exclude_patterns=["*.log"],
+        threads=1,
+        max_pending_tasks=1,
+        sort_by="path",
+        chunk_callback=collected.extend,
+    )
+
+    assert [entry["relative_path"] for entry in collected] == [
+        "backend/main.py",
+        "frontend/src/app.js",
+    ]
+    assert structure["frontend"]["src"]["app.js"]["content"] == "app\n"
+    assert summary["included_files"] == 2
+    assert summary["excluded_files"] == 1
+
+
+def test_relative_path_spans_nested_directories_and_root_labels(tmp_path: Path):
+    from samuraizer.backend.output.formatters.jsonl.jsonl_output import output_to_jsonl
+
+    docs = tmp_path / "docs"
+    code = tmp_path / "code"
+    (docs / "guide" / "deep").mkdir(parents=True)
+    (code / "pkg").mkdir(parents=True)
+    (docs / "index.md").write_text("index\n", encoding="utf-8")
+    (docs / "guide" / "deep" / "page.md").write_text("page\n", encoding="utf-8")
+    (code / "pkg" / "mod.py").write_text("mod\n", encoding="utf-8")
+    expected = ["code/pkg/mod.py", "docs/guide/deep/page.md", "docs/index.md"]
+
+    nested = []
+    _run(docs, chunk_callback=nested.extend)
+    assert sorted(entry["relative_path"] for entry in nested) == ["guide/deep/page.md", "index.md"]
+
+    collected = []
+    _run([docs, code], chunk_callback=collected.extend)
+    assert sorted(entry["relative_path"] for entry in collected) == expected
+    for entry in collected:
+        assert entry["relative_path"] == f"{entry['parent']}/{entry['filename']}"
+
+    output_file = tmp_path / "out.jsonl"
+    output_to_jsonl(iter(collected), str(output_file))
+    lines = output_file.read_text(encoding="utf-8").splitlines()
+    assert sorted(json.loads(line)["path"] for line in lines) == expected
+
+
+def test_roots_with_the_same_name_are_labelled_by_index(tmp_path: Path):
+    first = tmp_path / "a" / "src"
+    second = tmp_path / "b" / "src"
+    first.mkdir(parents=True)
+    second.mkdir(parents=True)
+    (first / "one.txt").write_text("1", encoding="utf-8")
+    (second / "two.txt").write_text("2", encoding="utf-8")
+
+    structure, _ = _run([first, second])
+
+    assert set(structure) == {"0", "1"}
+    assert "one.txt" in structure["0"]
+    assert "two.txt" in structure["1"]
+
+
+def test_canonicalize_root_resolves_and_deduplicates(tmp_path: Path):
+    project = tmp_path / "project"
+    (project / "src").mkdir(parents=True)
+    (project / "src" / "main.py").write_text("main\n", encoding="utf-8")
+    spelled = project / "src" / ".." / "src"
+
+    structure, _ = _run(spelled, canonicalize_root=True)
+    assert "main.py" in structure
+
+    structure, summary = _run([project, project / "src" / ".."], canonicalize_root=True)
+    assert summary["included_files"] == 1
+    assert "main.py" in structure["project"]["src"]
+
+    with pytest.raises(FileNotFoundError, match="missing"):
+        _run(tmp_path / "missing", canonicalize_root=True)
+
+
+def test_missing_root_raises_on_first_iteration(monkeypatch, tmp_path: Path):
+    from samuraizer.backend.analysis.traversal.traversal_core import RootNotFoundError
+
+    not_a_dir = tmp_path / "file.txt"
+    not_a_dir.write_text("x", encoding="utf-8")
+
+    chunks = traversal_processor.generate_directory_chunks(
+        root_dir=tmp_path / "typo",
+        max_file_size=1024,
+        include_binary=True,
+        excluded_folders=set(),
+        excluded_files=set(),
+        follow_symlinks=False,
+        image_extensions=set(),
+        exclude_patterns=[],
+        threads=1,
+        encoding=None,
+        hashing_enabled=False,
+        progress_callback=None,
+        cancellation_token=None,
+        chunk_size=16,
+        max_pending_tasks=None,
+    )
+    with pytest.raises(RootNotFoundError, match="does not exist"):
+        next(chunks)
+    with pytest.raises(RootNotFoundError, match="is not a directory"):
+        _run(not_a_dir)
+
+    valid = tmp_path / "valid"
+    valid.mkdir()
+    (valid / "a.txt").write_text("a", encoding="utf-8")
+    processed = []
+
+    def fake_process_file(file_path: Path, *args, **kwargs):
+        processed.append(file_path)
+        return file_path.name, {"type": "text", "content": ""}
+
+    monkeypatch.setattr(traversal_processor, "process_file", fake_process_file)
+    with pytest.raises(RootNotFoundError, match="does not exist"):
+        _run([valid, tmp_path / "typo"])
+    assert processed == []
+
+
+def test_encoding_by_extension_overrides_detection(tmp_path: Path):
+    text = "title = 日本語のテキスト\n" * 20
+    (tmp_path / "legacy.sjis").write_bytes(text.encode("shift_jis"))
+    (tmp_path / "modern.txt").write_text("plain ascii\n", encoding="utf-8")
+
+    structure, _ = _run(tmp_path, encoding_by_extension={"SJIS": "shift_jis"})
+
+    legacy = structure["legacy.sjis"]
+    assert legacy["encoding"] == "shift_jis"
+    assert legacy["content"] == text
+    assert structure["modern.txt"]["encoding"] != "shift_jis"
+
+
+def test_on_error_abort_stops_at_the_first_failed_file(monkeypatch, tmp_path: Path):
+    for index in range(20):
+        (tmp_path / f"file{index:02d}.txt").write_text("data\n", encoding="utf-8")
+    original = traversal_processor.process_file
+
+    def fake_process_file(file_path: Path, *args, **kwargs):
+        if file_path.name == "file03.txt":
+            return file_path.name, {"type": "error", "content": "unreadable"}
+        return original(file_path, *args, **kwargs)
+
+    monkeypatch.setattr(traversal_processor, "process_file", fake_process_file)
+    collected = []
+
+    with pytest.raises(traversal_processor.TraversalAbortedError) as excinfo:
+        _run(
+            tmp_path,
+            on_error="abort",
+            sort_by="name",
+            threads=1,
+            max_pending_tasks=1,
+            chunk_callback=collected.extend,
+        )
+
+    assert excinfo.value.path == tmp_path / "file03.txt"
+    assert [entry["filename"] for entry in collected] == [
+        "file00.txt",
+        "file01.txt",
+        "file02.txt",
+        "file03.txt",
+    ]
+
+    structure, _ = _run(tmp_path)
+    assert structure["file03.txt"]["type"] == "error"
+    assert len(structure) == 20
+
+
+def test_max_pending_tasks_below_one_is_rejected(tmp_path: Path):
+    (tmp_path / "a.txt").write_text("a\n", encoding="utf-8")
+
+    for value in (0, -1):
+        with pytest.raises(ValueError):
+            _run(tmp_path, max_pending_tasks=value)
+
+    structure, _ = _run(tmp_path, max_pending_tasks=1)
+    assert structure["a.txt"]["type"] == "text"
+
+
+def test_final_sort_orders_entries_by_computed_fields(tmp_path: Path):
+    (tmp_path / "sub").mkdir()
+    (tmp_path / "small.txt").write_text("a\n", encoding="utf-8")
+    (tmp_path / "sub" / "large.txt").write_text("a" * 100, encoding="utf-8")
+    (tmp_path / "medium.txt").write_text("a" * 10, encoding="utf-8")
+    (tmp_path / "tie.txt").write_text("a" * 10, encoding="utf-8")
+    collected = []
+
+    _run(tmp_path, final_sort="-content_length", chunk_callback=collected.extend)
+
+    names = [entry["filename"] for entry in collected]
+    assert names == ["large.txt", "medium.txt", "tie.txt", "small.txt"]
+
+    with pytest.raises(ValueError):
+        _run(tmp_path, final_sort="colour")
+
+
+def test_final_sort_by_hash_uses_the_digest_without_hash_encoding(monkeypatch, tmp_path: Path):
+    from samuraizer.backend.analysis import file_processor
+
+    monkeypatch.setattr(file_processor, "is_cache_disabled", lambda: True)
+    contents = {f"{index}.txt": f"content {index}\n" for index in range(6)}
+    for name, text in contents.items():
+        (tmp_path / name).write_text(text, encoding="utf-8")
+    collected = []
+
+    _run(tmp_path, hashing_enabled=True, final_sort="hash", chunk_callback=collected.extend)
+
+    digests = {
+        name: HashService.compute_bytes_hash(text.encode("utf-8")) for name, text in contents.items()
+    }
+    assert [entry["filename"] for entry in collected] == sorted(contents, key=digests.get)
+    assert all("file_hash" not in entry["info"] for entry in collected)
+
+    with pytest.raises(ValueError):
+        _run(tmp_path, final_sort="-hash")
+
+
+def test_traverse_with_callback_pushes_entries_and_summary(tmp_path: Path):
+    for name in ("a.txt", "b.txt", "c.txt"):
+        (tmp_path / name).write_text(name, encoding="utf-8")
+    options = {
+        "root_dir": tmp_path,
+        "max_file_size": 1024,
+        "include_binary": True,
+        "excluded_folders": set(),
+        "excluded_files": set(),
+        "follow_symlinks": False,
+        "image_extensions": set(),
+        "exclude_patterns": [],
+        "threads": 1,
+        "hashing_enabled": False,
+        "sort_by": "name",
+    }
+    entries = []
+    summaries = []
+
+    traversal_processor.traverse_with_callback(
+        entries.append, summaries.append, **options
+    )
+
+    assert [entry["filename"] for entry in entries] == ["a.txt", "b.txt", "c.txt"]
+    assert summaries[0]["processed_files"] == 3
+
+    def failing(_entry):
+        raise RuntimeError("consumer failed")
+
+    with pytest.raises(RuntimeError, match="consumer failed"):
+        traversal_processor.traverse_with_callback(failing, **options)
+
+
+def test_callback_errors_propagate_while_entries_are_buffered(monkeypatch, tmp_path: Path):
+    for directory in ("one", "three", "two"):
+        (tmp_path / directory).mkdir()
+        for name in ("a.txt", "b.txt"):
+            (tmp_path / directory / name).write_text(name, encoding="utf-8")
+    options = {
+        "root_dir": tmp_path,
+        "max_file_size": 1024,
+        "include_binary": True,
+        "excluded_folders": set(),
+        "excluded_files": set(),
+        "follow_symlinks": False,
+        "image_extensions": set(),
+        "exclude_patterns": [],
+        "threads": 2,
+        "hashing_enabled": False,
+        "chunk_size": 1,
+        "sort_by": "path",
+        "group_by_parent": True,
+    }
+    original = traversal_processor.process_file
+    two_started = threading.Event()
+    consumer_failed = threading.Event()
+
+    def gated_process_file(file_path: Path, *args, **kwargs):
+        relative = f"{file_path.parent.name}/{file_path.name}"
+        if relative == "one/b.txt":
+            # Completes "one" only once "two" holds a buffered entry.
+            two_started.wait(5)
+        elif relative == "two/b.txt":
+            consumer_failed.wait(5)
+        result = original(file_path, *args, **kwargs)
+        if relative == "two/a.txt":
+            two_started.set()
+        return result
+
+    class ConsumerError(Exception):
+        pass
+
+    def failing(_entry):
+        consumer_failed.set()
+        raise ConsumerError()
+
+    monkeypatch.setattr(traversal_processor, "process_file", gated_process_file)
+
+    with pytest.raises(ConsumerError):
+        traversal_processor.traverse_with_callback(failing, **options)
+
+
+def test_expand_archives_emits_member_entries(tmp_path: Path):
+    import io
+    import tarfile
+    import zipfile
+
+    (tmp_path / "dist").mkdir()
+    with zipfile.ZipFile(tmp_path / "dist" / "bundle.zip", "w") as archive:
+        archive.writestr("docs/readme.txt", "hello from zip\n")
+        archive.writestr("../escape.txt", "nope\n")
+    with tarfile.open(tmp_path / "src.tar.gz", "w:gz") as archive:
+        data = b"print('hi')\n"
+        info = tarfile.TarInfo("pkg/main.py")
+        info.size = len(data)
+        archive.addfile(info, io.BytesIO(data))
+    (tmp_path / "broken.zip").write_bytes(b"not a zip")
+
+    structure, summary = _run(tmp_path, expand_archives=True)
+
+    assert structure["dist"]["bundle.zip"]["docs"]["readme.txt"]["content"] == "hello from zip\n"
+    assert "escape.txt" not in structure["dist"]["bundle.zip"]
+    assert structure["src.tar.gz"]["pkg"]["main.py"]["content"] == "print('hi')\n"
+    assert structure["broken.zip"]["type"] == "binary"
+    assert summary["processed_files"] == 3
+
+    plain, _ = _run(tmp_path)
+    assert plain["dist"]["bundle.zip"]["type"] == "binary"
+
+
+def test_summary_keys_do_not_depend_on_the_outcome(tmp_path: Path):
+    _, empty = _run(tmp_path)
+    (tmp_path / "a.txt").write_text("alpha\n", encoding="utf-8")
+    _, budgeted = _run(tmp_path, max_total_content_bytes=1)
+
+    assert set(empty) == set(budgeted)
+    assert empty["failed_files"] == []
+    assert empty["excluded_breakdown"] == {}
+    assert empty["content_budget_exhausted"] is False
+    assert "hash_algorithm" not in empty
+    for key in ("unchanged_files", "deleted", "deleted_files", "lines_by_extension"):
+        assert empty[key] is None
+
+    db_path = tmp_path / "cache.db"
+    _, counted = _run(tmp_path, line_count_only=True, cache_db_path=db_path)
+    (tmp_path / "a.txt").unlink()
+    _, counted_empty = _run(
+        tmp_path, line_count_only=True, cache_db_path=db_path, changed_since_cache=True
+    )
+
+    assert set(counted) == set(counted_empty) == set(empty)
+    assert counted["lines_by_extension"] == {".txt": {"files": 1, "lines": 1}}
+    assert counted["deleted_files"] == []
+    assert counted_empty["lines_by_extension"] == {}
+    assert counted_empty["unchanged_files"] == 0
+
+
+def test_unexpected_exception_only_fails_its_own_file(monkeypatch, tmp_path: Path):
+    from samuraizer.backend.analysis import file_processor
+
+    (tmp_path / "good.txt").write_text("fine\n", encoding="utf-8")
+    (tmp_path / "bad.txt").write_text("boom\n", encoding="utf-8")
+    real_process_file = file_processor.process_file
+
+    def flaky_process_file(file_path, *args, **kwargs):
+        if file_path.name == "bad.txt":
+            raise RecursionError("bug in a handler")
+        return real_process_file(file_path, *args, **kwargs)
+
+    monkeypatch.setattr(traversal_processor, "process_file", flaky_process_file)
+
+    structure, summary = _run(tmp_path)
+
+    assert structure["good.txt"]["type"] == "text"
+    assert structure["bad.txt"]["reason"] == "panic"
+    assert summary["failed_files"] == [{"file": str(tmp_path / "bad.txt"), "error": "bug in a handler"}]
+
+
+def test_changed_since_cache_emits_only_changes(tmp_path: Path):
+    import sqlite3
+
+    root = tmp_path / "repo"
+    root.mkdir()
+    for name in ("same.txt", "edited.txt", "new.txt"):
+        (root / name).write_text(f"{name}\n", encoding="utf-8")
+    db_path = tmp_path / "cache.db"
+    same = (root / "same.txt").resolve()
+    gone = str(root.resolve() / "gone.txt")
+    with sqlite3.connect(db_path) as conn:
+        conn.execute(
+            "CREATE TABLE cache (file_path TEXT PRIMARY KEY, file_hash TEXT, "
+            "file_info TEXT, size INTEGER, mtime REAL)"
+        )
+        conn.executemany(
+            "INSERT INTO cache VALUES (?, NULL, '{}', ?, ?)",
+            [
+                (str(same), same.stat().st_size, same.stat().st_mtime),
+                (str((root / "edited.txt").resolve()), 1, 0.0),
+                (gone, 1, 0.0),
+                (str(tmp_path / "elsewhere" / "other.txt"), 1, 0.0),
+            ],
+        )
+
+    collected = []
+    _, summary = _run(
+        root,
+        cache_db_path=db_path,
+        changed_since_cache=True,
+        chunk_callback=collected.extend,
+    )
+
+    changes = {entry["filename"]: entry["changed"] for entry in collected}
+    assert changes == {"edited.txt": "modified", "new.txt": "added"}
+    assert summary["unchanged_files"] == 1
+    assert summary["deleted"] == [gone]
+    with pytest.raises(ValueError, match="cache_db_path"):
+        _run(root, changed_since_cache=True)
+
+
+def test_cache_db_path_reports_deleted_files(tmp_path: Path):
+    import sqlite3
+
+    root = tmp_path / "repo"
+    root.mkdir()
+    (root / "kept.txt").write_text("kept\n", encoding="utf-8")
+    (root / "excluded.log").write_text("log\n", encoding="utf-8")
+    db_path = tmp_path / "cache.db"
+    resolved = root.resolve()
+    with sqlite3.connect(db_path) as conn:
+        conn.execute(
+            "CREATE TABLE cache (file_path TEXT PRIMARY KEY, file_hash TEXT, "
+            "file_info TEXT, size INTEGER, mtime REAL)"
+        )
+        conn.executemany(
+            "INSERT INTO cache VALUES (?, NULL, '{}', 1, 0.0)",
+            [(str(resolved / name),) for name in ("kept.txt", "excluded.log", "gone.txt")],
+        )
+
+    structure, summary = _run(root, cache_db_path=db_path, exclude_patterns=["*.log"])
+    _, without_cache = _run(root)
+
+    assert set(structure) == {"kept.txt"}
+    assert summary["deleted_files"] == [str(resolved / "gone.txt")]
+    assert without_cache["deleted_files"] is None
+
+
+def test_missing_sqlite_keeps_the_cache_disabled(monkeypatch, tmp_path: Path):
+    from samuraizer.backend.cache import connection_pool
+    from samuraizer.backend.cache.cache_snapshot import CacheSnapshot
+    from samuraizer.backend.cache.cache_state import CacheStateManager
+
+    db_path = tmp_path / "cache.db"
+    db_path.touch()
+    previous = CacheStateManager.is_disabled()
+    monkeypatch.setattr(connection_pool, "sqlite3", None)
+    try:
+        connection_pool.set_cache_disabled(False)
+        assert connection_pool.is_cache_disabled() is True
+    finally:
+        CacheStateManager.set_disabled(previous)
+
+    assert CacheSnapshot.load(db_path).rows == {}
+
+
+def test_line_count_only_counts_lines_without_content(tmp_path: Path):
+    (tmp_path / "a.py").write_text("one\ntwo\nthree", encoding="utf-8")
+    (tmp_path / "b.py").write_text("x = 1\n", encoding="utf-8")
+    (tmp_path / "notes.md").write_text("# title\n\ntext\n", encoding="utf-8")
+    (tmp_path / "blob.bin").write_bytes(bytes(range(256)) * 4)
+
+    structure, summary = _run(tmp_path, line_count_only=True)
+
+    assert structure["a.py"]["line_count"] == 3
+    assert "content" not in structure["a.py"]
+    assert structure["blob.bin"]["content_omitted"] is True
+    assert summary["lines_by_extension"] == {
+        ".md": {"files": 1, "lines": 3},
+        ".py": {"files": 2, "lines": 4},
+    }
+
+
+def test_enrich_callback_merges_keys_and_contains_failures(tmp_path: Path):
+    (tmp_path / "good.txt").write_text("fine\n", encoding="utf-8")
+    (tmp_path / "bad.txt").write_text("boom\n", encoding="utf-8")
+    collected = []
+
+    def enrich(entry):
+        if entry["filename"] == "bad.txt":
+            raise KeyError("project")
+        return {"project_id": entry["filename"].upper()}
+
+    _, summary = _run(tmp_path, enrich_callback=enrich, chunk_callback=collected.extend)
+
+    by_name = {entry["filename"]: entry for entry in collected}
+    assert by_name["good.txt"]["project_id"] == "GOOD.TXT"
+    assert by_name["bad.txt"]["info"]["reason"] == "enrich_callback"
+    assert "project_id" not in by_name["bad.txt"]
+    assert [item["file"] for item in summary["failed_files"]] == [str(tmp_path / "bad.txt")]
+
+
+def test_url_encode_paths_adds_a_percent_encoded_path(tmp_path: Path):
+    (tmp_path / "my docs").mkdir()
+    (tmp_path / "my docs" / "ä#1?.txt").write_text("x\n", encoding="utf-8")
+    collected = []
+
+    _run(tmp_path, url_encode_paths=True, chunk_callback=collected.extend)
+
+    (entry,) = collected
+    assert entry["relative_path"] == "my docs/ä#1?.txt"
+    assert entry["url_path"] == "my%20docs/%C3%A4%231%3F.txt"
+
+
+def test_stream_failures_emits_them_and_caps_the_summary(tmp_path: Path):
+    for index in range(3):
+        (tmp_path / f"{index}.txt").write_text("x\n", encoding="utf-8")
+
+    def broken(_entry):
+        raise RuntimeError("no project")
+
+    chunks = traversal_processor.generate_directory_chunks(
+        root_dir=tmp_path,
+        max_file_size=1024,
+        include_binary=True,
+        excluded_folders=set(),
+        excluded_files=set(),
+        follow_symlinks=False,
+        image_extensions=set(),
+        exclude_patterns=[],
+        threads=1,
+        encoding=None,
+        hashing_enabled=False,
+        progress_callback=None,
+        cancellation_token=None,
+        chunk_size=16,
+        max_pending_tasks=None,
+        enrich_callback=broken,
+        stream_failures=True,
+        max_failed_files=1,
+    )
+    payloads = list(chunks)
+
+    failures = [payload["failure"] for payload in payloads if "failure" in payload]
+    summary = payloads[-1]["summary"]
+    assert sorted(failure["file"] for failure in failures) == [
+        str(tmp_path / f"{index}.txt") for index in range(3)
+    ]
+    assert len(summary["failed_files"]) == 1
+    assert summary["failed_count"] == 3
+    assert summary["failed_files_truncated"] is True
+
+
+def test_max_open_files_limits_concurrent_processing(monkeypatch, tmp_path: Path):
+    import threading
+    import time
+
+    for index in range(6):
+        (tmp_path / f"{index}.txt").write_text("x\n", encoding="utf-8")
+    lock = threading.Lock()
+    active = [0, 0]
+
+    def tracking_process_file(file_path, *_args, **_kwargs):
+        with lock:
+            active[0] += 1
+            active[1] = max(active[1], active[0])
+        time.sleep(0.01)
+        with lock:
+            active[0] -= 1
+        return file_path.name, {"type": "text", "content": ""}
+
+    monkeypatch.setattr(traversal_processor, "process_file", tracking_process_file)
+
+    structure, _ = _run(tmp_path, threads=4, max_open_files=2)
+
+    assert len(structure) == 6
+    assert active[1] <= 2
+
+
+def test_summary_echoes_the_effective_options(tmp_path: Path):
+    (tmp_path / "a.txt").write_text("a\n", encoding="utf-8")
+    many = [f"*.tmp{index}" for index in range(60)]
+
+    _, summary = _run(tmp_path, excluded_folders={"node_modules", ".git"}, exclude_patterns=many)
+
+    assert summary["options"] == {
+        "roots": [str(tmp_path.resolve())],
+        "threads": 2,
+        "max_file_size": 1024 * 1024,
+        "hash_algorithm": None,
+        "excluded_folders": [".git", "node_modules"],
+        "excluded_files": [],
+        "exclude_patterns": {"count": 60},
+    }
+
+
+@pytest.mark.skipif(not hasattr(os, "mkfifo"), reason="FIFOs unsupported")
+def test_fifo_is_reported_without_being_read(tmp_path: Path):
+    (tmp_path / "a.txt").write_text("a\n", encoding="utf-8")
+    os.mkfifo(tmp_path / "pipe")
+
+    structure, summary = _run(tmp_path)
+
+    assert structure["pipe"]["type"] == "special"
+    assert structure["pipe"]["kind"] == "fifo"
+    assert structure["a.txt"]["type"] == "text"
+    assert summary["failed_files"] == []
+
+
+def test_include_empty_dirs_reports_directories_without_children(tmp_path: Path):
+    (tmp_path / "empty").mkdir()
+    (tmp_path / "only_tmp").mkdir()
+    (tmp_path / "only_tmp" / "scratch.tmp").write_text("x\n", encoding="utf-8")
+    (tmp_path / "full").mkdir()
+    (tmp_path / "full" / "a.txt").write_text("a\n", encoding="utf-8")
+    (tmp_path / "nested" / "leaf").mkdir(parents=True)
+
+    plain, _ = _run(tmp_path, exclude_patterns=["*.tmp"])
+    structure, _ = _run(tmp_path, exclude_patterns=["*.tmp"], include_empty_dirs=True)
+
+    directory = {"type": "directory", "is_empty": True}
+    assert "empty" not in plain
+    assert structure["empty"] == directory
+    assert structure["only_tmp"] == directory
+    assert structure["nested"] == {"leaf": directory}
+    assert structure["full"]["a.txt"]["type"] == "text"
+
+
+def test_magic_overrides_do_not_leak_into_later_traversals(tmp_path: Path):
+    (tmp_path / "level.pak").write_bytes(b"GAME" + b"plain looking text\n" * 10)
+    overrides = [{"hex_prefix": "47414d45", "is_binary": True}]
+
+    with_overrides, _ = _run(tmp_path, magic_overrides=overrides)
+    without, _ = _run(tmp_path)
+
+    assert with_overrides["level.pak"]["type"] == "binary"
+    assert without["level.pak"]["type"] == "text"
+
+
+def test_invalid_magic_overrides_are_rejected(tmp_path: Path):
+    with pytest.raises(ValueError):
+        _run(tmp_path, magic_overrides=[{"hex_prefix": "zz", "is_binary": True}])
+
+
+def test_textual_mime_types_do_not_leak_into_later_traversals(monkeypatch, tmp_path: Path):
+    from samuraizer.utils.file_utils import mime_detection
+
+    (tmp_path / "manifest").write_text("name: demo\n", encoding="utf-8")
+
+    class YamlMagic:
+        def from_buffer(self, _sample: bytes) -> str:
+            return "application/x-yaml"
+
+    monkeypatch.setattr(mime_detection, "analyse_sample", lambda _sample: None)
+    monkeypatch.setattr(mime_detection, "get_magic_instance", lambda: YamlMagic())
+    mime_detection.clear_mime_cache()
+
+    with_extras, _ = _run(tmp_path, textual_mime_types=["application/x-yaml"])
+    without, _ = _run(tmp_path)
+
+    assert with_extras["manifest"]["type"] == "text"
+    assert without["manifest"]["type"] == "binary"